@@ -0,0 +1,122 @@
+use crate::theme::Theme;
+use crate::i18n::{tr, tr_with};
+use eframe::egui::{self, Color32, Context, FontData, FontDefinitions, FontFamily};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+// Font file used for the dyslexia-friendly option; not bundled, players drop it in next to the game
+static DYSLEXIA_FONT_PATH: &str = "fonts/OpenDyslexic-Regular.otf";
+static DYSLEXIA_FONT_NAME: &str = "OpenDyslexic";
+
+// Palettes that keep right/wrong distinguishable for common kinds of color blindness
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum ColorBlindMode{
+    #[default] Off,
+    RedGreen, // Deuteranopia and protanopia
+    BlueYellow, // Tritanopia
+}
+
+impl ColorBlindMode{
+    pub const ALL: [ColorBlindMode; 3] = [ColorBlindMode::Off, ColorBlindMode::RedGreen, ColorBlindMode::BlueYellow];
+
+    pub fn label(&self) -> String{
+        match self{
+            ColorBlindMode::Off => tr("accessibility.off"),
+            ColorBlindMode::RedGreen => tr("accessibility.red_green"),
+            ColorBlindMode::BlueYellow => tr("accessibility.blue_yellow"),
+        }
+    }
+}
+
+// Accessibility preferences, saved as part of the settings
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct AccessibilitySettings{
+    pub color_blind: ColorBlindMode,
+    pub history_markers: bool, // Put a check or cross next to each guess instead of relying on color
+    pub text_scale: f32,       // Multiplier for every font size in the theme
+    pub dyslexia_font: bool,   // Use the dyslexia-friendly font when it is installed
+}
+
+impl Default for AccessibilitySettings{
+    fn default() -> Self{
+        Self{
+            color_blind: ColorBlindMode::Off,
+            history_markers: false,
+            text_scale: 1.0,
+            dyslexia_font: false}
+    }
+}
+
+/*
+The adapt_theme/2 function returns a copy of `theme` with the accessibility settings applied:
+the right/wrong colors are swapped for a color-blind safe pair (from the Okabe-Ito palette) and
+every font size is multiplied by the text scale.
+*/
+pub fn adapt_theme(theme: &Theme, settings: &AccessibilitySettings) -> Theme{
+    let mut adapted = theme.clone();
+    let palette = &mut adapted.palette;
+    match settings.color_blind{
+        ColorBlindMode::RedGreen => {
+            palette.correct = Color32::from_rgb(0, 114, 178);             // Blue
+            palette.incorrect = Color32::from_rgb(230, 159, 0);           // Orange
+            palette.feedback_correct = Color32::from_rgb(0, 114, 178);
+            palette.feedback_misplaced = Color32::from_rgb(230, 159, 0);},
+        ColorBlindMode::BlueYellow => {
+            palette.correct = Color32::from_rgb(0, 158, 115);             // Bluish green
+            palette.incorrect = Color32::from_rgb(204, 121, 167);         // Reddish purple
+            palette.feedback_correct = Color32::from_rgb(0, 158, 115);
+            palette.feedback_misplaced = Color32::from_rgb(204, 121, 167);},
+        ColorBlindMode::Off => (),
+    }
+    let fonts = &mut adapted.fonts;
+    fonts.tile_scale = (fonts.tile_scale * settings.text_scale).min(0.95); // Letters must still fit their tile
+    fonts.heading_size *= settings.text_scale;
+    fonts.body_size *= settings.text_scale;
+    fonts.history_size *= settings.text_scale;
+    adapted
+}
+
+// Function to get the marker drawn next to a guess in the history
+pub fn history_marker(correct: bool) -> &'static str{
+    if correct { "✔" } else { "✖" }
+}
+
+/*
+The install_fonts/2 function sets egui's fonts, putting the dyslexia-friendly font in front of the
+default fonts when it is wanted. Returns false if the font was wanted but its file couldn't be read,
+in which case the default fonts are used.
+*/
+pub fn install_fonts(ctx: &Context, dyslexia_font: bool) -> bool{
+    let mut fonts = FontDefinitions::default();
+    let mut installed = true;
+    if dyslexia_font{
+        match fs::read(DYSLEXIA_FONT_PATH){
+            Ok(data) => {
+                fonts.font_data.insert(DYSLEXIA_FONT_NAME.into(), FontData::from_owned(data));
+                for family in [FontFamily::Proportional, FontFamily::Monospace]{
+                    fonts.families.entry(family).or_default().insert(0, DYSLEXIA_FONT_NAME.into());
+                }},
+            Err(_) => installed = false,
+        }
+    }
+    ctx.set_fonts(fonts);
+    installed
+}
+
+// Function to show the accessibility section of the settings window
+pub fn accessibility_settings(ui: &mut egui::Ui, settings: &mut AccessibilitySettings, font_missing: bool){
+    egui::ComboBox::from_label(tr("accessibility.color_blind_palette"))
+        .selected_text(settings.color_blind.label())
+        .show_ui(ui, |ui|{
+            for mode in ColorBlindMode::ALL{
+                ui.selectable_value(&mut settings.color_blind, mode, mode.label());
+            }
+        });
+    ui.checkbox(&mut settings.history_markers, tr("accessibility.history_markers"));
+    ui.add(egui::Slider::new(&mut settings.text_scale, 0.75..=2.0).text(tr("accessibility.text_scale")));
+    ui.checkbox(&mut settings.dyslexia_font, tr("accessibility.dyslexia_font"));
+    if settings.dyslexia_font && font_missing{
+        ui.label(tr_with("accessibility.font_missing", &[("path", &DYSLEXIA_FONT_PATH)]));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::shape_builder::MorphShape;
+use crate::ui_elements::TileSlot;
+use eframe::egui::Shape;
+use emath::{Pos2, Vec2};
+use std::time::{Duration, Instant};
+
+// Constants for how tiles glide, shake and pop
+static GLIDE_SPEED: f32 = 14.0;                                  // Higher glides faster (exponential easing rate per second)
+static SNAP_DISTANCE: f32 = 0.5;                                 // Tiles closer than this to their slot snap into place
+static MAX_FRAME_TIME: f32 = 0.05;                               // Longest step taken in one frame, so idle gaps don't skip the glide
+static SHAKE_DURATION: Duration = Duration::from_millis(400);
+static SHAKE_AMPLITUDE: f32 = 8.0;                               // Pixels either side of the slot
+static SHAKE_FREQUENCY: f32 = 40.0;                              // Radians per second
+static POP_DURATION: Duration = Duration::from_millis(250);
+static POP_SIZE: f32 = 6.0;                                      // Pixels each tile grows by at the start of a pop
+
+// Struct for remembering where tiles were drawn so they can glide to their new slots
+pub struct TileAnimator{
+    tiles: Vec<(char, Pos2)>,      // Letter and top left corner of every tile drawn last frame
+    gliding: bool,                 // A tile has not reached its slot yet
+    shake_started: Option<Instant>,
+    pop_started: Option<Instant>,
+    last_frame: Instant,
+}
+
+impl Default for TileAnimator{
+    fn default() -> Self{
+        Self{
+            tiles: Vec::new(),
+            gliding: false,
+            shake_started: None,
+            pop_started: None,
+            last_frame: Instant::now()}
+    }
+}
+
+impl TileAnimator{
+    // Function to start the wrong guess shake
+    pub fn shake(&mut self){
+        self.shake_started = Some(Instant::now());
+    }
+
+    // Function to start the correct guess pop
+    pub fn pop(&mut self){
+        self.pop_started = Some(Instant::now());
+    }
+
+    // Function to check if the correct guess pop is still playing
+    pub fn is_popping(&self) -> bool{
+        self.pop_started.is_some()
+    }
+
+    // Function to check if another frame is needed to finish an animation
+    pub fn is_animating(&self) -> bool{
+        self.gliding || self.shake_started.is_some() || self.pop_started.is_some()
+    }
+
+    /*
+    The animate/3 function moves freshly generated tiles from their slots to where they should be drawn this frame.
+    Tiles have no identity of their own, so each tile is matched to a tile of the same letter from last frame:
+    tiles that have not moved are matched first, then the remaining tiles take the closest tile left over.
+    Matched tiles ease toward their slot; unmatched tiles appear in place. With reduced motion every tile
+    is drawn in its slot and shakes and pops are skipped.
+    */
+    pub fn animate(&mut self, squares: &mut [(Shape, char, TileSlot)], reduced_motion: bool){
+        let elapsed = self.last_frame.elapsed().as_secs_f32().min(MAX_FRAME_TIME);
+        self.last_frame = Instant::now();
+        let ease = 1.0 - (-GLIDE_SPEED * elapsed).exp();
+
+        let targets: Vec<(char, Pos2)> = squares.iter()
+            .filter_map(|(shape, letter, _)| match shape {
+                Shape::Rect(container) => Some((*letter, container.rect.min)),
+                _ => None})
+            .collect();
+        let starts = self.match_previous(&targets);
+
+        let shake = self.effect_progress(Effect::Shake, reduced_motion);
+        let pop = self.effect_progress(Effect::Pop, reduced_motion);
+        let mut moving = false;
+        let mut drawn = Vec::with_capacity(targets.len());
+
+        for ((shape, letter, _), start) in squares.iter_mut().filter(|(shape, _, _)| matches!(shape, Shape::Rect(_))).zip(starts){
+            if let Shape::Rect(container) = shape{
+                let target = container.rect.min;
+                let mut position = match start{
+                    Some(start) if !reduced_motion => start + (target - start) * ease,
+                    _ => target};
+                if position.distance(target) < SNAP_DISTANCE{
+                    position = target;
+                }
+                moving |= position != target;
+                drawn.push((*letter, position));
+
+                if let Some(progress) = shake{ // Decaying side to side wobble
+                    let t = progress * SHAKE_DURATION.as_secs_f32();
+                    position += Vec2::from(((t * SHAKE_FREQUENCY).sin() * SHAKE_AMPLITUDE * (1.0 - progress), 0.0));
+                }
+                let mut moved = container.move_to(position - target);
+                if let Some(progress) = pop{ // Grow then settle back to size
+                    moved = moved.resize(POP_SIZE * (1.0 - progress), POP_SIZE * (1.0 - progress));
+                }
+                *container = moved;
+            }
+        }
+
+        self.tiles = drawn;
+        self.gliding = moving;
+    }
+
+    // Function to pick a starting point for each target from the tiles drawn last frame
+    fn match_previous(&self, targets: &[(char, Pos2)]) -> Vec<Option<Pos2>>{
+        let mut previous: Vec<Option<(char, Pos2)>> = self.tiles.iter().copied().map(Some).collect();
+        let mut starts = vec![None; targets.len()];
+
+        // Pass 1: tiles that are already sitting in their slot
+        for (i, (letter, target)) in targets.iter().enumerate(){
+            if let Some(tile) = previous.iter_mut().find(|tile| matches!(tile, Some((l, p)) if l == letter && p.distance(*target) < SNAP_DISTANCE)){
+                starts[i] = tile.take().map(|(_, p)| p);
+            }
+        }
+
+        // Pass 2: closest leftover tile with the same letter
+        for (i, (letter, target)) in targets.iter().enumerate(){
+            if starts[i].is_some(){
+                continue;
+            }
+            let closest = previous.iter_mut()
+                .filter(|tile| matches!(tile, Some((l, _)) if l == letter))
+                .min_by(|a, b| distance_to(a, *target).total_cmp(&distance_to(b, *target)));
+            if let Some(tile) = closest{
+                starts[i] = tile.take().map(|(_, p)| p);
+            }
+        }
+        starts
+    }
+
+    // Function to find how far through an effect we are (0.0 to 1.0), clearing it once finished
+    fn effect_progress(&mut self, effect: Effect, reduced_motion: bool) -> Option<f32>{
+        let (started, duration) = match effect{
+            Effect::Shake => (&mut self.shake_started, SHAKE_DURATION),
+            Effect::Pop => (&mut self.pop_started, POP_DURATION)};
+        let progress = started.map(|start| start.elapsed().as_secs_f32() / duration.as_secs_f32());
+        match progress{
+            Some(progress) if progress < 1.0 && !reduced_motion => Some(progress),
+            _ => {*started = None;
+                  None}
+        }
+    }
+}
+
+enum Effect{
+    Shake,
+    Pop,
+}
+
+fn distance_to(tile: &Option<(char, Pos2)>, target: Pos2) -> f32{
+    tile.map_or(f32::INFINITY, |(_, p)| p.distance(target))
+}
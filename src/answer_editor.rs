@@ -0,0 +1,319 @@
+use crate::WordUnscramblerApp;
+use crate::{api, language};
+use eframe::egui::{Key, Modifiers};
+
+// Actions a player can perform on letter tiles with the mouse
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileAction{
+    Take(usize),           // Move the rack tile at this position to the caret in the answer
+    Return(usize),         // Move the answer tile at this position back to the rack
+    Reorder(usize, usize), // Move an answer tile from one answer position to another
+}
+
+// Copy of the rack, answer and caret taken before every tile move so it can be undone
+#[derive(Clone, Debug, PartialEq)]
+struct TileSnapshot{
+    rack: String,
+    answer: String,
+    cursor: usize,
+    blank_letter: Option<char>,
+}
+
+// Undo/redo stacks for tile moves within the current word
+#[derive(Default)]
+pub struct TileHistory{
+    undo: Vec<TileSnapshot>,
+    redo: Vec<TileSnapshot>,
+}
+
+// Moving letters between the scrambled rack and the answer tray
+pub trait MoveTiles{
+    fn take_from_rack(&mut self, index: usize) -> &mut Self;           // Rack tile -> caret position in answer
+    fn return_to_rack(&mut self, index: usize) -> &mut Self;           // Answer tile -> end of rack
+    fn reorder_answer(&mut self, from: usize, to: usize) -> &mut Self; // Answer tile -> other answer slot
+    fn type_letter(&mut self, letter: char) -> &mut Self;              // Typed letter -> caret position in answer
+    fn remove_last_letter(&mut self) -> &mut Self;                     // Answer tile before the caret -> end of rack
+    fn apply_tile_action(&mut self, action: TileAction) -> &mut Self;  // Dispatch a mouse action
+}
+
+// Caret movement, forward deletion, swapping and undo/redo within the answer tray
+pub trait EditAnswer{
+    fn move_cursor(&mut self, offset: isize) -> &mut Self; // Move the caret left (-) or right (+)
+    fn set_cursor(&mut self, cursor: usize) -> &mut Self;  // Place the caret, clamped to the answer
+    fn delete_forward(&mut self) -> &mut Self;             // Answer tile after the caret -> end of rack
+    fn swap_adjacent(&mut self, offset: isize) -> &mut Self; // Carry the tile before the caret left (-) or right (+)
+    fn undo(&mut self) -> &mut Self;
+    fn redo(&mut self) -> &mut Self;
+    fn reset_editor(&mut self) -> &mut Self;               // Clear caret and history when the word changes
+    fn handle_editor_key(&mut self, key: Key, modifiers: Modifiers) -> &mut Self; // Keyboard shortcuts
+}
+
+impl WordUnscramblerApp{
+    // Function to snapshot the tiles before a move and drop any redo steps
+    fn record_move(&mut self){
+        let snapshot = self.snapshot();
+        self.tile_history.undo.push(snapshot);
+        self.tile_history.redo.clear();
+    }
+
+    fn snapshot(&self) -> TileSnapshot{
+        TileSnapshot{
+            rack: self.game_state.scrambled_word.clone(),
+            answer: self.input_text.clone(),
+            cursor: self.cursor,
+            blank_letter: self.blank_letter}
+    }
+
+    fn restore(&mut self, snapshot: TileSnapshot){
+        self.game_state.scrambled_word = snapshot.rack;
+        self.input_text = snapshot.answer;
+        self.cursor = snapshot.cursor;
+        self.blank_letter = snapshot.blank_letter;
+    }
+
+    // Function to get the answer with the blank tile swapped for the letter the player gave it
+    pub fn resolved_answer(&self) -> String{
+        match self.blank_letter{
+            Some(letter) => self.input_text.replace(api::BLANK, &letter.to_string()),
+            None => self.input_text.clone(),
+        }
+    }
+}
+
+impl MoveTiles for WordUnscramblerApp{
+    // Function to move the rack tile at `index` into the answer at the caret
+    fn take_from_rack(&mut self, index: usize) -> &mut Self{
+        let mut rack: Vec<char> = self.game_state.scrambled_word.chars().collect();
+        if index < rack.len(){
+            self.record_move();
+            let mut answer: Vec<char> = self.input_text.chars().collect();
+            let cursor = self.cursor.min(answer.len());
+            answer.insert(cursor, rack.remove(index));
+            self.game_state.scrambled_word = rack.into_iter().collect();
+            self.input_text = answer.into_iter().collect();
+            self.cursor = cursor + 1;
+        }
+        self
+    }
+
+    // Function to move the answer tile at `index` back onto the end of the rack
+    fn return_to_rack(&mut self, index: usize) -> &mut Self{
+        let mut answer: Vec<char> = self.input_text.chars().collect();
+        if index < answer.len(){
+            self.record_move();
+            let letter = answer.remove(index);
+            if letter == api::BLANK{ // A blank goes back to the rack empty
+                self.blank_letter = None;
+            }
+            self.game_state.scrambled_word.push(letter);
+            self.input_text = answer.into_iter().collect();
+            if index < self.cursor{ // Keep the caret next to the same tiles
+                self.cursor -= 1;
+            }
+        }
+        self
+    }
+
+    // Function to move an answer tile to another slot, shifting the tiles in between
+    fn reorder_answer(&mut self, from: usize, to: usize) -> &mut Self{
+        let mut answer: Vec<char> = self.input_text.chars().collect();
+        if from < answer.len() && from != to{
+            self.record_move();
+            let letter = answer.remove(from);
+            let to = to.min(answer.len());
+            answer.insert(to, letter);
+            self.input_text = answer.into_iter().collect();
+            self.cursor = to + 1; // Caret follows the moved tile
+        }
+        self
+    }
+
+    // Function to take the first rack tile matching a typed letter
+    fn type_letter(&mut self, letter: char) -> &mut Self{
+        // Match the tile exactly if possible; otherwise an unaccented letter takes an accented tile ('e' -> 'é')
+        // for keyboards without the accent
+        let letter = letter.to_lowercase().next().unwrap_or(letter);
+        let rack: Vec<char> = self.game_state.scrambled_word.chars().collect();
+        let index = rack.iter().position(|c| *c == letter)
+            .or_else(|| rack.iter().position(|c| language::base_letter(*c) == letter));
+        match index{
+            Some(index) => { self.take_from_rack(index); },
+            // No tile has the letter, so it goes on the blank: taken from the rack, or already in the answer
+            None if letter.is_alphabetic() => {
+                if let Some(blank) = rack.iter().position(|c| *c == api::BLANK){
+                    self.take_from_rack(blank);
+                    self.blank_letter = Some(letter);
+                }
+                else if self.input_text.contains(api::BLANK){
+                    self.record_move();
+                    self.blank_letter = Some(letter);
+                }},
+            None => (),
+        }
+        self
+    }
+
+    // Function to return the tile just before the caret to the rack
+    fn remove_last_letter(&mut self) -> &mut Self{
+        if self.cursor > 0{
+            self.return_to_rack(self.cursor - 1);
+        }
+        self
+    }
+
+    fn apply_tile_action(&mut self, action: TileAction) -> &mut Self{
+        match action{
+            TileAction::Take(index) => self.take_from_rack(index),
+            TileAction::Return(index) => self.return_to_rack(index),
+            TileAction::Reorder(from, to) => self.reorder_answer(from, to),
+        }
+    }
+}
+
+impl EditAnswer for WordUnscramblerApp{
+    fn move_cursor(&mut self, offset: isize) -> &mut Self{
+        self.set_cursor(self.cursor.saturating_add_signed(offset))
+    }
+
+    fn set_cursor(&mut self, cursor: usize) -> &mut Self{
+        self.cursor = cursor.min(self.input_text.chars().count());
+        self
+    }
+
+    // Function to return the tile just after the caret to the rack
+    fn delete_forward(&mut self) -> &mut Self{
+        if self.cursor < self.input_text.chars().count(){
+            self.return_to_rack(self.cursor);
+        }
+        self
+    }
+
+    // Function to swap the tile before the caret with its neighbour, carrying the caret along
+    fn swap_adjacent(&mut self, offset: isize) -> &mut Self{
+        let answer_length = self.input_text.chars().count();
+        if self.cursor == 0{
+            return self;
+        }
+        let from = self.cursor - 1;
+        match from.checked_add_signed(offset.signum()){
+            Some(to) if to < answer_length => self.reorder_answer(from, to),
+            _ => self,
+        }
+    }
+
+    fn undo(&mut self) -> &mut Self{
+        if let Some(snapshot) = self.tile_history.undo.pop(){
+            let current = self.snapshot();
+            self.tile_history.redo.push(current);
+            self.restore(snapshot);
+        }
+        self
+    }
+
+    fn redo(&mut self) -> &mut Self{
+        if let Some(snapshot) = self.tile_history.redo.pop(){
+            let current = self.snapshot();
+            self.tile_history.undo.push(current);
+            self.restore(snapshot);
+        }
+        self
+    }
+
+    fn reset_editor(&mut self) -> &mut Self{
+        self.cursor = 0;
+        self.blank_letter = None;
+        self.tile_history = TileHistory::default();
+        self
+    }
+
+    // Function to map editing keys onto the operations above
+    fn handle_editor_key(&mut self, key: Key, modifiers: Modifiers) -> &mut Self{
+        match key{
+            Key::Z if modifiers.command && modifiers.shift => self.redo(),
+            Key::Z if modifiers.command => self.undo(),
+            Key::Y if modifiers.command => self.redo(),
+            Key::ArrowLeft if modifiers.shift => self.swap_adjacent(-1),
+            Key::ArrowRight if modifiers.shift => self.swap_adjacent(1),
+            Key::ArrowLeft => self.move_cursor(-1),
+            Key::ArrowRight => self.move_cursor(1),
+            Key::Home => self.set_cursor(0),
+            Key::End => self.set_cursor(usize::MAX),
+            Key::Backspace => self.remove_last_letter(),
+            Key::Delete => self.delete_forward(),
+            _ => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn app(rack: &str, answer: &str, cursor: usize) -> WordUnscramblerApp{
+        let mut app = WordUnscramblerApp::default();
+        app.game_state.scrambled_word = rack.into();
+        app.input_text = answer.into();
+        app.cursor = cursor;
+        app
+    }
+
+    #[test]
+    fn caret_is_clamped_to_the_answer(){
+        let mut app = app("", "abc", 1);
+        app.set_cursor(usize::MAX);
+        assert_eq!(app.cursor, 3);
+        app.move_cursor(-5);
+        assert_eq!(app.cursor, 0);
+        app.move_cursor(10);
+        assert_eq!(app.cursor, 3);
+    }
+
+    #[test]
+    fn swap_adjacent_stops_at_both_ends(){
+        let mut app = app("", "abc", 0);
+        app.swap_adjacent(-1); // No tile before the caret
+        assert_eq!((app.input_text.as_str(), app.cursor), ("abc", 0));
+
+        app.set_cursor(1).swap_adjacent(-1); // "a" is already first
+        assert_eq!((app.input_text.as_str(), app.cursor), ("abc", 1));
+
+        app.set_cursor(3).swap_adjacent(1); // "c" is already last
+        assert_eq!((app.input_text.as_str(), app.cursor), ("abc", 3));
+
+        app.swap_adjacent(-1);
+        assert_eq!((app.input_text.as_str(), app.cursor), ("acb", 2));
+    }
+
+    #[test]
+    fn reordered_tile_carries_the_caret(){
+        let mut app = app("", "abcd", 0);
+        app.reorder_answer(0, 2);
+        assert_eq!((app.input_text.as_str(), app.cursor), ("bcad", 3));
+        app.reorder_answer(3, 0);
+        assert_eq!((app.input_text.as_str(), app.cursor), ("dbca", 1));
+    }
+
+    #[test]
+    fn undo_and_redo_round_trip(){
+        let mut app = app("cat", "", 0);
+        app.take_from_rack(2).take_from_rack(0);
+        assert_eq!((app.game_state.scrambled_word.as_str(), app.input_text.as_str(), app.cursor), ("a", "tc", 2));
+
+        app.undo().undo();
+        assert_eq!((app.game_state.scrambled_word.as_str(), app.input_text.as_str(), app.cursor), ("cat", "", 0));
+        app.undo(); // Nothing left to undo
+        assert_eq!(app.game_state.scrambled_word, "cat");
+
+        app.redo().redo();
+        assert_eq!((app.game_state.scrambled_word.as_str(), app.input_text.as_str(), app.cursor), ("a", "tc", 2));
+    }
+
+    #[test]
+    fn new_move_clears_redo(){
+        let mut app = app("cat", "", 0);
+        app.take_from_rack(0).undo();
+        app.take_from_rack(1);
+        app.redo();
+        assert_eq!((app.game_state.scrambled_word.as_str(), app.input_text.as_str()), ("ct", "a"));
+    }
+}
@@ -1,35 +1,230 @@
 use rand::seq::SliceRandom; // Import SliceRandom to shuffle slices
-use ureq;                   // Import ureq for HTTP requests
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use crate::{definitions, dictionary, dictionary_cache, language, word_filter, word_history, word_pack};
+use crate::definitions::WordDefinition;
+use crate::language::Language;
+use crate::difficulty::{self, Band, Tier};
+use crate::http::{self, ApiError};
+use std::time::{Duration, Instant};
 
-// Function to get random word from API return scrambled version
-pub fn get_scrambled_word(length: usize) -> Option<(String, String)> {
+static CANDIDATE_WORDS: usize = 10; // Words requested at once so a recently served one can be passed over
+static FRESH_ATTEMPTS: usize = 3;   // API requests made looking for a word that wasn't served recently
+
+// Round trip time of the most recent request in milliseconds (u64::MAX until a request finishes)
+static LAST_LATENCY_MS: AtomicU64 = AtomicU64::new(u64::MAX);
+
+// Function to get the round trip time of the most recent request, for the debug overlay
+pub fn last_latency() -> Option<Duration> {
+    match LAST_LATENCY_MS.load(Ordering::Relaxed) {
+        u64::MAX => None,
+        ms => Some(Duration::from_millis(ms)),
+    }
+}
+
+// Seeded generator used for scrambling when a seed has been set (dev console), otherwise thread_rng
+static SEEDED_RNG: Mutex<Option<StdRng>> = Mutex::new(None);
+
+// Function to make scrambles repeatable from a seed, or random again with None
+pub fn set_seed(seed: Option<u64>) {
+    if let Ok(mut rng) = SEEDED_RNG.lock() {
+        *rng = seed.map(StdRng::seed_from_u64);
+    }
+}
+
+// Function to shuffle the letters of a word
+pub fn scramble(word: &str) -> String {
+    let mut chars: Vec<char> = language::normalize(word).chars().collect(); // Split original word into vector of chars, one per accented letter
+    match SEEDED_RNG.lock().as_deref_mut() {
+        Ok(Some(rng)) => chars.shuffle(rng),
+        _ => chars.shuffle(&mut rand::thread_rng()),
+    }
+    chars.into_iter().collect()                                          // Form string from scrambled chars
+}
+
+// Character that stands for the blank tile in missing-letter mode
+pub static BLANK: char = '_';
+
+// Function to turn one tile of a scrambled word into a blank the player has to fill in
+pub fn add_blank(scrambled: &str) -> String {
+    let mut chars: Vec<char> = scrambled.chars().collect();
+    if !chars.is_empty() {
+        let index = rand::thread_rng().gen_range(0..chars.len());
+        chars[index] = BLANK;
+    }
+    chars.into_iter().collect()
+}
+
+/*
+The add_decoys/3 function mixes `count` extra letters into a scrambled word. Decoys are taken from
+random words in the local word list so they look like letters of the language being played, and
+letters already in the answer are avoided so a decoy is never needed to spell it.
+*/
+pub fn add_decoys(scrambled: &str, original: &str, count: usize) -> String {
+    let mut rng = rand::thread_rng();
+    let mut decoys = String::new();
+    for _ in 0..count {
+        let source = dictionary::random_word(rng.gen_range(3..=10), |_| true).unwrap_or_default();
+        let unused: Vec<char> = source.chars().chain('a'..='z').filter(|letter| !original.contains(*letter)).collect();
+        if let Some(letter) = unused.choose(&mut rng) {
+            decoys.push(*letter);
+        }
+    }
+    scramble(&format!("{}{}", scrambled, decoys))
+}
+
+// Function to remember how long a request took
+fn record_latency(started: Instant) {
+    LAST_LATENCY_MS.store(started.elapsed().as_millis() as u64, Ordering::Relaxed);
+}
+
+// Result of checking a guess against the dictionary
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WordCheck {
+    Valid,
+    Invalid,
+    Unverified, // Dictionary couldn't be reached and the local word list doesn't know the word
+}
+
+// Function to get random word from API return scrambled version (None if the content filter or `wanted` rejected every word)
+pub fn get_scrambled_word(tier: Tier, wanted: impl Fn(&str) -> bool) -> Result<Option<(String, String)>, ApiError> {
+    let language = match language::active() {
+        Language::English => String::new(),                              // English is the API's default word list
+        other => format!("&lang={}", other.code()),
+    };
     let url = format!(
-        "https://random-word-api.herokuapp.com/word?number=1&length={}", // URL for random word API fitting length requirements
-        length
+        "https://random-word-api.herokuapp.com/word?number={}&length={}{}", // URL for random word API fitting length and language requirements
+        CANDIDATE_WORDS, tier.length, language
     );
-    let response = ureq::get(&url).call().ok()?;                         // GET request to API; get response
-    let words: Vec<String> = response.into_json().ok()?;                 // parse response into JSON vector of words (none if it fails to parse)
+    let started = Instant::now();
+    let body = http::get(&url);                                          // GET request to API; get response
+    record_latency(started);
+    let words: Vec<String> = serde_json::from_str::<Vec<String>>(&body?) // parse response into JSON vector of words
+        .map_err(|e| ApiError::Parse(e.to_string()))?
+        .iter().map(|word| language::normalize(word)).collect();
+    if words.is_empty() {
+        return Err(ApiError::Parse("no word in response".into()));
+    }
 
-    if let Some(word) = words.first() {                                  // If word has been retrieved
-        let mut chars: Vec<char> = word.chars().collect();               // Split original word into vector of chars
-        chars.shuffle(&mut rand::thread_rng());                          // Scramble chars 
-        let scrambled_word: String = chars.into_iter().collect();        // Form string from scrambled chars
-        Some((scrambled_word, word.clone()))                             // Tuple containing orginal and scrambled word
-    } else {                                                             // If no word retrieved, return none
-        None
+    // Prefer a word the player hasn't seen recently, from the requested difficulty band if there is one
+    let allowed: Vec<&String> = words.iter().filter(|word| wanted(word) && word_filter::allows(word)).collect();
+    let fresh = |word: &&&String| !word_history::is_recent(word);
+    let word = allowed.iter().filter(fresh).find(|word| difficulty::band(word) == tier.band)
+        .or_else(|| allowed.iter().find(fresh))
+        .or_else(|| allowed.first());
+    Ok(word.map(|word| (scramble(word), word.to_string())))              // Tuple containing scrambled and original word
+}
+
+/*
+The next_word/1 function gets a scrambled word for the next puzzle, asking the random word API
+first and falling back to the local word list if the API can't be used. Words the content filter
+rejects (see word_filter) are never served, and words served recently (see word_history) are
+skipped while there are others to choose from. A word from the tier's difficulty band is preferred,
+but the word length always wins over the band. API words are almost never in the local word list,
+so they have no frequency and rate hard; easy and medium tiers are served from the local word list
+first and only go to the API when it has no fresh word for the tier. With a minimum frequency or
+the allowlist in effect the API is skipped altogether (see word_filter::local_only). While a word
+pack is being played its words are used instead. Only words `wanted` accepts are served, from any
+source (word-chain mode uses it for the first letter). Returns None only if no source has a word.
+*/
+pub fn next_word(tier: Tier, wanted: impl Fn(&str) -> bool) -> Option<(String, String)> {
+    if let Some(pack) = word_pack::active() { // A chosen word pack replaces the random sources
+        return pack.next_word(tier, wanted).map(|word| (scramble(&word), word));
+    }
+    let in_tier = |word: &str| word.chars().count() == tier.length && difficulty::band(word) == tier.band;
+    let fresh = |word: &str| wanted(word) && word_filter::allows(word) && !word_history::is_recent(word);
+    if tier.band != Band::Hard {
+        if let Some(word) = dictionary::random_word(tier.length, |word| in_tier(word) && fresh(word)) {
+            return Some((scramble(&word), word));
+        }
     }
+    let mut off_band = None; // Fresh API word from another band, used if the word list has nothing in the band either
+    let attempts = if word_filter::local_only() { 0 } else { FRESH_ATTEMPTS };
+    for _ in 0..attempts {
+        match get_scrambled_word(tier, &wanted) {
+            Ok(None) => continue,                                        // Every word was filtered out
+            Ok(Some((_, original))) if word_history::is_recent(&original) => continue,
+            Ok(Some(word)) if difficulty::band(&word.1) == tier.band => return Some(word),
+            Ok(Some(word)) => { off_band.get_or_insert(word); }
+            Err(e) => {
+                log::warn!(target: "api", "random word request failed, using local word list: length={} error={}", tier.length, e);
+                break;
+            }
+        }
+    }
+    dictionary::random_word(tier.length, |word| in_tier(word) && fresh(word))
+        .map(|word| (scramble(&word), word))
+        .or(off_band)
+        .or_else(|| dictionary::random_word(tier.length, fresh).map(|word| (scramble(&word), word)))
+        .or_else(|| dictionary::random_word(tier.length, |word| wanted(word) && word_filter::allows(word)).map(|word| (scramble(&word), word))) // Everything was seen recently; allow a repeat
 }
 
-// Function to check for validity of word referencing the dictionary API
-pub fn is_valid_word(word: &str) -> bool {
-    let url = format!("https://api.dictionaryapi.dev/api/v2/entries/en/{}", word);  // URL of dictionary API
-    let response = ureq::get(&url).call();                                          // GET request to API; get response
+/*
+The lookup_word/1 function asks the dictionary API about a word and caches the answer, along with
+the definition parsed from the response. Returns whether the word is real and its definition.
+*/
+fn lookup_word(word: &str) -> Result<(bool, Option<WordDefinition>), ApiError> {
+    let language = language::active();
+    let cache_key = language.qualify(word);
+    let url = format!("https://api.dictionaryapi.dev/api/v2/entries/{}/{}", language.code(), word);  // URL of dictionary API
+    let started = Instant::now();
+    let response = http::get(&url);                                                 // GET request to API; get response
+    record_latency(started);
 
-    // Check if response is successful and return true/false depending on that success
-    match response {
-        Ok(resp) => resp.status() == 200,
-        Err(_) => false,
+    // A 404 means the dictionary doesn't know the word; anything else unexpected is an error
+    let (valid, definition) = match response {
+        Ok(body) => (true, definitions::parse(&body)),
+        Err(ApiError::Status(404)) => (false, None),
+        Err(e) => return Err(e),                                                    // Only real answers are cached
+    };
+    dictionary_cache::store(&cache_key, valid, definition.clone());
+    Ok((valid, definition))
+}
+
+// Function to check for validity of word referencing the dictionary API (or the cache of earlier answers)
+pub fn is_valid_word(word: &str) -> Result<bool, ApiError> {
+    match dictionary_cache::lookup(&language::active().qualify(word)) {
+        Some(valid) => Ok(valid),
+        None => lookup_word(word).map(|(valid, _)| valid),
     }
 }
 
+// Function to get a word's definition from the dictionary API (or the cache); None if it has none
+pub fn define(word: &str) -> Result<Option<WordDefinition>, ApiError> {
+    let cache_key = language::active().qualify(word);
+    if let Some(definition) = dictionary_cache::lookup_definition(&cache_key) {
+        return Ok(Some(definition));
+    }
+    if dictionary_cache::lookup(&cache_key) == Some(false) {
+        return Ok(None);
+    }
+    lookup_word(word).map(|(_, definition)| definition) // Unknown, or known real but cached before definitions were kept
+}
 
+// Function to check every word of a phrase; one unknown word makes the whole phrase invalid
+pub fn check_phrase(words: &[String]) -> WordCheck {
+    let mut result = WordCheck::Valid;
+    for word in words {
+        match check_word(word) {
+            WordCheck::Invalid => return WordCheck::Invalid,
+            WordCheck::Unverified => result = WordCheck::Unverified,
+            WordCheck::Valid => (),
+        }
+    }
+    result
+}
+
+// Function to check a word with the dictionary API, falling back to the local word list
+// (which also overrules a "not found" outside English, where the API knows far fewer words)
+pub fn check_word(word: &str) -> WordCheck {
+    match is_valid_word(word) {
+        Ok(true) => WordCheck::Valid,
+        Ok(false) if language::active() != Language::English && dictionary::contains(word) => WordCheck::Valid,
+        Ok(false) => WordCheck::Invalid,
+        Err(e) => {
+            log::warn!(target: "api", "dictionary lookup failed, using local word list: error={}", e);
+            if dictionary::contains(word) { WordCheck::Valid } else { WordCheck::Unverified }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{api, difficulty};
+use crate::game_state::GameState;
+use eframe::egui::{self, Align2, Context, Key, Vec2};
+use std::collections::VecDeque;
+use std::env;
+use std::time::{Duration, Instant};
+
+// Ways to turn on developer tools: a command line flag or an environment variable
+static DEV_FLAG: &str = "--dev";
+static DEV_ENV_VAR: &str = "WORD_UNSCRAMBLER_DEV";
+static FRAME_SAMPLES: usize = 120; // Frames averaged for the frame timing readout
+
+// Function to check whether the game was started with developer tools enabled
+pub fn dev_mode_enabled() -> bool{
+    env::args().any(|arg| arg == DEV_FLAG)
+        || env::var(DEV_ENV_VAR).is_ok_and(|value| value != "0" && !value.is_empty())
+}
+
+// Extra app state shown in the overlay alongside the game state
+pub struct OverlayDetails<'a>{
+    pub game_state: &'a GameState,
+    pub input_text: &'a str,
+    pub cursor: usize,
+    pub time_remaining: Duration,
+}
+
+// Struct for the developer overlay: hidden unless dev mode is on, toggled with F12
+pub struct DebugOverlay{
+    pub enabled: bool,
+    pub visible: bool,
+    frame_times: VecDeque<Duration>,
+    last_frame: Instant,
+}
+
+impl Default for DebugOverlay{
+    fn default() -> Self{
+        Self{
+            enabled: dev_mode_enabled(),
+            visible: false,
+            frame_times: VecDeque::with_capacity(FRAME_SAMPLES),
+            last_frame: Instant::now()}
+    }
+}
+
+impl DebugOverlay{
+    // Function to record the time since the last frame; called once per frame
+    pub fn record_frame(&mut self){
+        if self.frame_times.len() == FRAME_SAMPLES{
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(self.last_frame.elapsed());
+        self.last_frame = Instant::now();
+    }
+
+    // Function to get the average and worst frame time over the recent frames
+    fn frame_stats(&self) -> (Duration, Duration){
+        let total: Duration = self.frame_times.iter().sum();
+        let average = total / self.frame_times.len().max(1) as u32;
+        let worst = self.frame_times.iter().max().copied().unwrap_or_default();
+        (average, worst)
+    }
+
+    // Function to toggle and draw the overlay; does nothing outside dev mode
+    pub fn show(&mut self, ctx: &Context, details: OverlayDetails){
+        if !self.enabled{
+            return;
+        }
+        self.record_frame();
+        if ctx.input(|i| i.key_pressed(Key::F12)){
+            self.visible = !self.visible;
+        }
+        if !self.visible{
+            return;
+        }
+
+        let (average, worst) = self.frame_stats();
+        let state = details.game_state;
+        egui::Window::new("Debug")
+            .anchor(Align2::LEFT_TOP, Vec2::new(10.0, 40.0))
+            .resizable(false)
+            .collapsible(true)
+            .show(ctx, |ui|{
+                egui::Grid::new("debug_overlay_grid").num_columns(2).show(ui, |ui|{
+                    let mut row = |name: &str, value: String|{
+                        ui.label(name);
+                        ui.monospace(value);
+                        ui.end_row();
+                    };
+                    row("Answer", state.original_word.clone());
+                    row("Rack", format!("{:?} (full: {:?})", state.scrambled_word, state.restore_scrambled));
+                    row("Input", format!("{:?} caret={}", details.input_text, details.cursor));
+                    row("Score", state.score.to_string());
+                    row("Level", state.level.to_string());
+                    row("Language", state.language.code().to_string());
+                    row("Word pack", state.pack.clone().unwrap_or("random words".into()));
+                    row("Word length", state.word_length.to_string());
+                    row("Difficulty", format!("{} (current word scores {:.2})", state.band, difficulty::score(&state.original_word)));
+                    row("Wrong guesses", state.wrong_guesses.to_string());
+                    row("Time allotted", format!("{:.1}s", state.time_alotted.as_secs_f32()));
+                    row("Time remaining", format!("{:.1}s", details.time_remaining.as_secs_f32()));
+                    row("Prefetched", state.prefetcher.as_ref().map_or("not started".into(), |prefetcher|
+                        format!("{} ready ({}), {} ready ({})", prefetcher.ready(state.tier()), state.tier(),
+                                prefetcher.ready(state.next_tier()), state.next_tier())));
+                    row("Network latency", api::last_latency().map_or("n/a".into(), |latency| format!("{} ms", latency.as_millis())));
+                    row("Frame time", format!("{:.1} ms avg / {:.1} ms worst", average.as_secs_f32() * 1000.0, worst.as_secs_f32() * 1000.0));
+                });
+            });
+    }
+}
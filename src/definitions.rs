@@ -0,0 +1,280 @@
+use crate::api;
+use crate::i18n::{tr, tr_with};
+use crate::word_pack;
+use eframe::egui::{self, RichText};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// Constants for how much of a dictionary entry is kept
+static MAX_MEANINGS: usize = 3;        // Parts of speech kept per word
+static MAX_DEFINITIONS: usize = 2;     // Definitions kept per part of speech
+
+// One part of speech of a word and its definitions
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Meaning{
+    pub part_of_speech: String,
+    pub definitions: Vec<String>,
+}
+
+// Struct for what the dictionary says about a word
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WordDefinition{
+    pub phonetic: Option<String>, // Pronunciation, e.g. "/ˈæp.əl/"
+    pub meanings: Vec<Meaning>,
+}
+
+// Shape of a dictionaryapi.dev response; only the fields the word card uses
+#[derive(Deserialize)]
+struct ApiEntry{
+    phonetic: Option<String>,
+    #[serde(default)]
+    phonetics: Vec<ApiPhonetic>,
+    #[serde(default)]
+    meanings: Vec<ApiMeaning>,
+}
+
+#[derive(Deserialize)]
+struct ApiPhonetic{
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiMeaning{
+    part_of_speech: String,
+    #[serde(default)]
+    definitions: Vec<ApiDefinition>,
+}
+
+#[derive(Deserialize)]
+struct ApiDefinition{
+    definition: String,
+}
+
+/*
+The parse/1 function reads a dictionaryapi.dev response body. The API returns one entry per
+etymology; meanings are merged across entries by part of speech and trimmed to a few lines so the
+word card stays small. Returns None if the body isn't a dictionary entry or has no definitions.
+*/
+pub fn parse(body: &str) -> Option<WordDefinition>{
+    let entries: Vec<ApiEntry> = serde_json::from_str(body).ok()?;
+    let phonetic = entries.iter()
+        .flat_map(|entry| entry.phonetic.iter().chain(entry.phonetics.iter().filter_map(|phonetic| phonetic.text.as_ref())))
+        .find(|text| !text.trim().is_empty())
+        .cloned();
+
+    let mut meanings: Vec<Meaning> = Vec::new();
+    for meaning in entries.iter().flat_map(|entry| &entry.meanings){
+        let definitions = meaning.definitions.iter().map(|definition| definition.definition.clone());
+        match meanings.iter().position(|existing| existing.part_of_speech == meaning.part_of_speech){
+            Some(index) => meanings[index].definitions.extend(definitions),
+            None if meanings.len() < MAX_MEANINGS => meanings.push(Meaning{
+                part_of_speech: meaning.part_of_speech.clone(),
+                definitions: definitions.collect()}),
+            None => (),
+        }
+    }
+    for meaning in &mut meanings{
+        meaning.definitions.truncate(MAX_DEFINITIONS);
+    }
+    meanings.retain(|meaning| !meaning.definitions.is_empty());
+
+    (!meanings.is_empty()).then_some(WordDefinition{ phonetic, meanings })
+}
+
+// Function to get a definition from the word pack being played, for words the dictionary won't know
+pub fn from_pack(word: &str) -> Option<WordDefinition>{
+    let pack = word_pack::active()?;
+    let definition = pack.entry(word)?.definition.clone()?;
+    Some(WordDefinition{ phonetic: None, meanings: vec![Meaning{ part_of_speech: String::new(), definitions: vec![definition] }] })
+}
+
+// Lookup state of a word's definition
+#[derive(Clone, Debug, PartialEq)]
+pub enum Lookup{
+    Pending,
+    Found(WordDefinition),
+    Missing, // The dictionary has no entry, or couldn't be reached
+}
+
+// A word the player solved or skipped this game, shown on the word card and in the glossary
+#[derive(Clone, Debug)]
+pub struct GlossaryEntry{
+    pub word: String,
+    pub solved: bool,
+    pub lookup: Lookup,
+}
+
+// Struct for the words finished this game, with their definitions fetched in the background
+pub struct Glossary{
+    pub entries: Vec<GlossaryEntry>,
+    sender: Sender<(String, Option<WordDefinition>)>,
+    receiver: Receiver<(String, Option<WordDefinition>)>,
+}
+
+impl Default for Glossary{
+    fn default() -> Self{
+        let (sender, receiver) = mpsc::channel();
+        Self{ entries: Vec::new(), sender, receiver }
+    }
+}
+
+// Function to start finding a word's definition: straight from the word pack, or from the dictionary on a
+// background thread that sends the result to `sender`
+fn look_up(word: &str, sender: &Sender<(String, Option<WordDefinition>)>) -> Lookup{
+    if let Some(definition) = from_pack(word){
+        return Lookup::Found(definition);
+    }
+    let sender = sender.clone();
+    let word = word.to_string();
+    thread::spawn(move ||{
+        let definition = api::define(&word).unwrap_or_else(|e|{
+            log::warn!(target: "api", "definition lookup failed: error={}", e);
+            None});
+        let _ = sender.send((word, definition));
+    });
+    Lookup::Pending
+}
+
+impl Glossary{
+    // Function to add a finished word and start looking up its definition
+    pub fn add(&mut self, word: &str, solved: bool){
+        let lookup = look_up(word, &self.sender);
+        self.entries.push(GlossaryEntry{ word: word.to_string(), solved, lookup });
+    }
+
+    // Function to fill in definitions that have arrived; called once per frame
+    pub fn receive(&mut self){
+        while let Ok((word, definition)) = self.receiver.try_recv(){
+            for entry in self.entries.iter_mut().filter(|entry| entry.word == word && entry.lookup == Lookup::Pending){
+                entry.lookup = definition.clone().map_or(Lookup::Missing, Lookup::Found);
+            }
+        }
+    }
+
+    pub fn latest(&self) -> Option<&GlossaryEntry>{
+        self.entries.last()
+    }
+}
+
+// Function to draw a word card: the word, whether it was solved, its pronunciation and definitions
+pub fn word_card(ui: &mut egui::Ui, entry: &GlossaryEntry){
+    egui::Frame::group(ui.style()).show(ui, |ui|{
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui|{
+            ui.label(RichText::new(&entry.word).strong().size(18.0));
+            ui.weak(if entry.solved { tr("card.solved") } else { tr("card.skipped") });
+        });
+        match &entry.lookup{
+            Lookup::Pending => { ui.weak(tr("card.loading")); }
+            Lookup::Missing => { ui.weak(tr("card.no_definition")); }
+            Lookup::Found(definition) => {
+                if let Some(phonetic) = &definition.phonetic{
+                    ui.weak(phonetic);
+                }
+                for meaning in &definition.meanings{
+                    if !meaning.part_of_speech.is_empty(){
+                        ui.label(RichText::new(&meaning.part_of_speech).italics());
+                    }
+                    for (number, text) in meaning.definitions.iter().enumerate(){
+                        ui.label(format!("{}. {}", number + 1, text));
+                    }
+                }},
+        }
+    });
+}
+
+// Struct for the definition clue of the word being played, looked up in the background
+pub struct Clue{
+    word: String,
+    lookup: Lookup,
+    sender: Sender<(String, Option<WordDefinition>)>,
+    receiver: Receiver<(String, Option<WordDefinition>)>,
+}
+
+impl Default for Clue{
+    fn default() -> Self{
+        let (sender, receiver) = mpsc::channel();
+        Self{ word: String::new(), lookup: Lookup::Missing, sender, receiver }
+    }
+}
+
+impl Clue{
+    // Function to keep the clue on the word being played, starting a lookup when it changes; called every frame
+    pub fn follow(&mut self, word: &str){
+        while let Ok((looked_up, definition)) = self.receiver.try_recv(){
+            if looked_up == self.word{ // Answers for earlier words are dropped
+                self.lookup = definition.map_or(Lookup::Missing, Lookup::Found);
+            }
+        }
+        if word != self.word && !word.is_empty(){
+            self.word = word.to_string();
+            self.lookup = look_up(word, &self.sender);
+        }
+    }
+
+    // Function to get the first definition with the answer blanked out, since definitions often use the word itself
+    pub fn text(&self) -> Option<String>{
+        let Lookup::Found(definition) = &self.lookup else { return None };
+        let meaning = definition.meanings.first()?;
+        let text = meaning.definitions.first()?;
+        let blanked = match RegexBuilder::new(&regex::escape(&self.word)).case_insensitive(true).build(){
+            Ok(answer) => answer.replace_all(text, "_".repeat(self.word.chars().count())).into_owned(),
+            Err(_) => text.clone(),
+        };
+        Some(if meaning.part_of_speech.is_empty() { blanked } else { format!("({}) {}", meaning.part_of_speech, blanked) })
+    }
+}
+
+// Function to draw the clue bar: a button to reveal the clue, then the clue itself
+pub fn clue_bar(ui: &mut egui::Ui, clue: &Clue, revealed: &mut bool, bonus: u32){
+    ui.horizontal_wrapped(|ui|{
+        ui.strong(tr("clue.title"));
+        if !*revealed{
+            if ui.button(tr_with("clue.reveal", &[("bonus", &bonus)])).clicked(){
+                *revealed = true;
+            }
+            return;
+        }
+        match (&clue.lookup, clue.text()){
+            (_, Some(text)) => { ui.label(text); }
+            (Lookup::Pending, None) => { ui.weak(tr("clue.loading")); }
+            _ => { ui.weak(tr("clue.missing")); }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    // Two etymologies of "bark", shaped like a dictionaryapi.dev response
+    static BARK: &str = r#"[
+        {"word": "bark", "phonetic": "", "phonetics": [{"text": "/bɑːk/"}],
+         "meanings": [
+            {"partOfSpeech": "noun", "definitions": [{"definition": "The sound a dog makes."}, {"definition": "A short shout."}, {"definition": "A cough."}]},
+            {"partOfSpeech": "verb", "definitions": [{"definition": "To make the sound of a dog."}]}]},
+        {"word": "bark", "phonetics": [],
+         "meanings": [
+            {"partOfSpeech": "noun", "definitions": [{"definition": "The outer covering of a tree."}]},
+            {"partOfSpeech": "adjective", "definitions": [{"definition": "Made of bark."}]},
+            {"partOfSpeech": "interjection", "definitions": [{"definition": "Dropped, as only three parts of speech are kept."}]}]}
+    ]"#;
+
+    #[test]
+    fn meanings_merge_by_part_of_speech_and_are_capped(){
+        let definition = parse(BARK).expect("bark has definitions");
+        assert_eq!(definition.phonetic.as_deref(), Some("/bɑːk/")); // Blank phonetic skipped
+        let parts: Vec<&str> = definition.meanings.iter().map(|meaning| meaning.part_of_speech.as_str()).collect();
+        assert_eq!(parts, vec!["noun", "verb", "adjective"]);
+        assert_eq!(definition.meanings[0].definitions, vec!["The sound a dog makes.", "A short shout."]);
+    }
+
+    #[test]
+    fn body_without_definitions_is_none(){
+        assert_eq!(parse(r#"[{"word": "bark", "meanings": [{"partOfSpeech": "noun", "definitions": []}]}]"#), None);
+        assert_eq!(parse(r#"{"title": "No Definitions Found"}"#), None);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::api;
+use crate::difficulty::Band;
+use crate::game_state::{GameState, UpdateGameVariables};
+use eframe::egui::{self, Context, Key};
+use std::str::FromStr;
+
+static HELP: &str = "Commands: set-word <word>, add-time <seconds>, set-level <n>, set-length <n>, set-band <easy|medium|hard>, seed <n|off>, end-game, help";
+static MAX_LOG_LINES: usize = 50;
+
+// Commands understood by the developer console
+#[derive(Debug, PartialEq)]
+pub enum Command{
+    SetWord(String),   // Replace the current puzzle with this word
+    AddTime(i64),      // Add seconds to the clock (negative removes them)
+    SetLevel(u8),
+    SetLength(usize),  // Length of words fetched from now on
+    SetBand(Band),     // Difficulty band of words fetched from now on
+    Seed(Option<u64>), // Seed the scrambler, or None to go back to random
+    EndGame,
+    Help,
+}
+
+// Function to parse one line typed into the console
+pub fn parse_command(line: &str) -> Result<Command, String>{
+    let mut parts = line.split_whitespace();
+    let name = parts.next().ok_or_else(|| "Type a command, or help".to_string())?;
+    let argument = parts.next();
+
+    match name{
+        "set-word" => argument.map(|word| Command::SetWord(word.to_lowercase())).ok_or("set-word needs a word".into()),
+        "add-time" => parse_number(name, argument, "number of seconds").map(Command::AddTime),
+        "set-level" => parse_number(name, argument, "level (1-255)").map(Command::SetLevel),
+        "set-length" => parse_number(name, argument, "length").map(Command::SetLength),
+        "set-band" => argument.ok_or("set-band needs easy, medium or hard".to_string()).and_then(Band::from_str).map(Command::SetBand),
+        "seed" => match argument{
+            Some("off") => Ok(Command::Seed(None)),
+            _ => parse_number(name, argument, "seed").map(|seed| Command::Seed(Some(seed)))},
+        "end-game" => Ok(Command::EndGame),
+        "help" => Ok(Command::Help),
+        _ => Err(format!("Unknown command {}. {}", name, HELP)),
+    }
+}
+
+// Function to parse the numeric argument of a command
+fn parse_number<T: FromStr>(name: &str, argument: Option<&str>, what: &str) -> Result<T, String>{
+    let value = argument.ok_or(format!("{} needs a number", name))?;
+    value.parse().map_err(|_| format!("{} is not a valid {}", value, what))
+}
+
+// Function to run a command against the game state; returns the message shown in the console
+pub fn execute(command: Command, game_state: &mut GameState) -> String{
+    match command{
+        Command::SetWord(word) => {
+            game_state.set_word(api::scramble(&word), word.clone());
+            format!("Word set to {}", word)},
+        Command::AddTime(seconds) => {
+            game_state.add_time(seconds);
+            format!("Time allotted is now {}s", game_state.time_alotted.as_secs())},
+        Command::SetLevel(level) => {
+            game_state.set_level(level);
+            format!("Level set to {}", game_state.level)},
+        Command::SetLength(length) => {
+            game_state.set_word_length(length);
+            format!("Word length set to {}; applies to the next word", game_state.word_length)},
+        Command::SetBand(band) => {
+            game_state.set_band(band);
+            format!("Difficulty set to {}; applies to the next word", game_state.band)},
+        Command::Seed(seed) => {
+            api::set_seed(seed);
+            seed.map_or("Scrambling is random again".into(), |seed| format!("Scrambles seeded with {}", seed))},
+        Command::EndGame => {
+            game_state.end_game();
+            "Ending game".into()},
+        Command::Help => HELP.into(),
+    }
+}
+
+// Struct for the developer console window, opened with the backtick key in dev mode
+#[derive(Default)]
+pub struct DevConsole{
+    pub open: bool,
+    input: String,
+    log: Vec<String>, // Commands entered and their results, oldest first
+}
+
+impl DevConsole{
+    // Function to check if the console should get keyboard input instead of the game
+    pub fn wants_keyboard(&self) -> bool{
+        self.open
+    }
+
+    // Function to toggle and draw the console, running any command entered this frame.
+    // Returns true if a command replaced the current word, so the caller can clear the answer tray
+    pub fn show(&mut self, ctx: &Context, enabled: bool, game_state: &mut GameState) -> bool{
+        if !enabled{
+            return false;
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, Key::Backtick)){
+            self.open = !self.open;
+        }
+        if !self.open{
+            return false;
+        }
+
+        let mut submitted = None;
+        egui::Window::new("Console")
+            .default_width(420.0)
+            .collapsible(false)
+            .show(ctx, |ui|{
+                egui::ScrollArea::vertical().max_height(200.0).stick_to_bottom(true).show(ui, |ui|{
+                    for line in &self.log{
+                        ui.monospace(line);
+                    }
+                });
+                let response = ui.add(egui::TextEdit::singleline(&mut self.input).hint_text("help").desired_width(f32::INFINITY));
+                self.input.retain(|c| c != '`'); // The toggle key also arrives as typed text
+                if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)){
+                    submitted = Some(std::mem::take(&mut self.input));
+                }
+                response.request_focus();
+            });
+
+        let mut word_changed = false;
+        if let Some(line) = submitted{
+            let result = match parse_command(&line){
+                Ok(command) => {
+                    word_changed = matches!(command, Command::SetWord(_));
+                    execute(command, game_state)},
+                Err(e) => e};
+            log::info!(target: "console", "command={:?} result={:?}", line, result);
+            self.log.push(format!("> {}", line));
+            self.log.push(result);
+            if self.log.len() > MAX_LOG_LINES{
+                self.log.drain(..self.log.len() - MAX_LOG_LINES);
+            }
+        }
+        word_changed
+    }
+}
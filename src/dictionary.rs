@@ -0,0 +1,97 @@
+use crate::language::{self, Language};
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Word lists compiled into the game so it can keep going without the network, one per language
+// Each line is a word and its approximate Zipf frequency, separated by a tab; lines starting with # are comments
+static WORD_LIST: &str = include_str!("../assets/words.txt");
+static WORD_LIST_ES: &str = include_str!("../assets/words_es.txt");
+static WORD_LIST_DE: &str = include_str!("../assets/words_de.txt");
+static WORD_LIST_FR: &str = include_str!("../assets/words_fr.txt");
+
+// Function to read a word list into words and frequencies; words are normalized so accents compare equal
+fn parse_word_list(list: &str) -> HashMap<String, f32>{
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line|{
+            let mut fields = line.split('\t');
+            let word = language::normalize(fields.next()?.trim());
+            let frequency = fields.next().and_then(|field| field.trim().parse().ok()).unwrap_or(0.0);
+            Some((word, frequency))})
+        .collect()
+}
+
+// Function to get the local word list for a language
+fn word_list(language: Language) -> &'static HashMap<String, f32>{
+    static WORDS: OnceLock<HashMap<Language, HashMap<String, f32>>> = OnceLock::new();
+    let lists = WORDS.get_or_init(|| Language::ALL.into_iter().map(|language|{
+        let list = match language{
+            Language::English => WORD_LIST,
+            Language::Spanish => WORD_LIST_ES,
+            Language::German => WORD_LIST_DE,
+            Language::French => WORD_LIST_FR,
+        };
+        (language, parse_word_list(list))}).collect());
+    &lists[&language]
+}
+
+// Function to get the local word list for the language being played
+fn words() -> &'static HashMap<String, f32>{
+    word_list(language::active())
+}
+
+// Function to list every word in the local word list for the language being played
+pub fn all_words() -> Vec<&'static str>{
+    words().keys().map(String::as_str).collect()
+}
+
+// Function to check if a word is in the local word list
+pub fn contains(word: &str) -> bool{
+    words().contains_key(&language::normalize(word))
+}
+
+// Function to get a word's Zipf frequency (log10 of uses per billion words), if the word list has it
+pub fn frequency(word: &str) -> Option<f32>{
+    words().get(&language::normalize(word)).copied()
+}
+
+// Function to get a word's letters in sorted order, which every anagram of it shares
+fn letter_key(word: &str) -> String{
+    let mut letters: Vec<char> = language::normalize(word).chars().collect();
+    letters.sort_unstable();
+    letters.into_iter().collect()
+}
+
+// Function to count the words in the local word list made of exactly the same letters (including the word itself)
+pub fn anagram_count(word: &str) -> usize{
+    static ANAGRAMS: OnceLock<HashMap<Language, HashMap<String, usize>>> = OnceLock::new();
+    let anagrams = ANAGRAMS.get_or_init(|| Language::ALL.into_iter().map(|language|{
+        let mut counts = HashMap::new();
+        for word in word_list(language).keys(){
+            *counts.entry(letter_key(word)).or_insert(0) += 1;
+        }
+        (language, counts)}).collect());
+    anagrams[&language::active()].get(&letter_key(word)).copied().unwrap_or(0)
+}
+
+/*
+The random_word/2 function picks a local word of the requested length that passes `allowed`.
+If no allowed word has that length it uses the closest length that has one, preferring shorter
+words on a tie.
+*/
+pub fn random_word(length: usize, allowed: impl Fn(&str) -> bool) -> Option<String>{
+    let words: Vec<&str> = words().keys().map(String::as_str).collect();
+    closest_word(&words, length, allowed)
+}
+
+// Function to pick a word from any list the same way random_word picks from the local word list
+pub fn closest_word(words: &[&str], length: usize, allowed: impl Fn(&str) -> bool) -> Option<String>{
+    let allowed_words: Vec<&str> = words.iter().copied().filter(|word| allowed(word)).collect();
+    let closest = allowed_words.iter().map(|word| word.chars().count())
+        .min_by_key(|candidate| (candidate.abs_diff(length), *candidate))?;
+
+    let candidates: Vec<&&str> = allowed_words.iter().filter(|word| word.chars().count() == closest).collect();
+    candidates.choose(&mut rand::thread_rng()).map(|word| word.to_string())
+}
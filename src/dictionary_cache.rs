@@ -0,0 +1,168 @@
+use crate::definitions::WordDefinition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Constants for where dictionary answers are kept and for how long
+static CACHE_FILE: &str = "dictionary_cache.json";
+static VALID_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60); // Real words stay real; keep them a month
+static INVALID_TTL: Duration = Duration::from_secs(24 * 60 * 60);    // Dictionaries grow; recheck rejected words daily
+static MAX_ENTRIES: usize = 5000;
+static SAVE_EVERY: usize = 20; // New answers held in memory before the file is rewritten; the rest are saved on exit
+
+// One remembered dictionary answer
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct CacheEntry{
+    valid: bool,
+    checked_at: u64, // Seconds since the Unix epoch
+    #[serde(default)]
+    definition: Option<WordDefinition>, // Kept when the dictionary's answer included one
+}
+
+impl CacheEntry{
+    fn is_fresh(&self, now: u64) -> bool{
+        let ttl = if self.valid { VALID_TTL } else { INVALID_TTL };
+        now.saturating_sub(self.checked_at) < ttl.as_secs()
+    }
+}
+
+// Struct for dictionary answers saved between runs so repeated guesses skip the network
+#[derive(Serialize, Deserialize, Default)]
+pub struct DictionaryCache{
+    entries: HashMap<String, CacheEntry>,
+    #[serde(skip)]
+    unsaved: usize, // Answers added since the file was last written
+}
+
+impl DictionaryCache{
+    // Function to read the cache file, starting empty if it is missing or unreadable
+    fn load() -> Self{
+        fs::read_to_string(CACHE_FILE).ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&mut self){
+        match serde_json::to_string(self){
+            Ok(data) => if let Err(e) = fs::write(CACHE_FILE, data){
+                log::warn!(target: "cache", "could not save dictionary cache: {}", e);},
+            Err(e) => log::warn!(target: "cache", "could not serialize dictionary cache: {}", e),
+        }
+        self.unsaved = 0;
+    }
+
+    fn get(&self, word: &str, now: u64) -> Option<&CacheEntry>{
+        self.entries.get(word).filter(|entry| entry.is_fresh(now))
+    }
+
+    // Function to add an answer, dropping expired entries and then the oldest ones once over the cap
+    fn insert(&mut self, word: &str, valid: bool, definition: Option<WordDefinition>, now: u64){
+        self.entries.insert(word.to_string(), CacheEntry{ valid, checked_at: now, definition });
+        self.unsaved += 1;
+        if self.entries.len() > MAX_ENTRIES{
+            self.entries.retain(|_, entry| entry.is_fresh(now));
+        }
+        if self.entries.len() > MAX_ENTRIES{
+            let mut by_age: Vec<(String, u64)> = self.entries.iter().map(|(word, entry)| (word.clone(), entry.checked_at)).collect();
+            by_age.sort_by_key(|(_, checked_at)| *checked_at);
+            for (word, _) in by_age.into_iter().take(self.entries.len() - MAX_ENTRIES){
+                self.entries.remove(&word);
+            }
+        }
+    }
+}
+
+fn cache() -> &'static Mutex<DictionaryCache>{
+    static CACHE: OnceLock<Mutex<DictionaryCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(DictionaryCache::load()))
+}
+
+fn now() -> u64{
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+// Function to get a remembered answer for a word, if it hasn't expired
+pub fn lookup(word: &str) -> Option<bool>{
+    cache().lock().ok()?.get(&word.to_lowercase(), now()).map(|entry| entry.valid)
+}
+
+// Function to get a remembered definition for a word, if one was saved and hasn't expired
+pub fn lookup_definition(word: &str) -> Option<WordDefinition>{
+    cache().lock().ok()?.get(&word.to_lowercase(), now())?.definition.clone()
+}
+
+// Function to remember the dictionary's answer for a word, and its definition if it had one;
+// the file is rewritten every SAVE_EVERY answers rather than on every lookup
+pub fn store(word: &str, valid: bool, definition: Option<WordDefinition>){
+    if let Ok(mut cache) = cache().lock(){
+        cache.insert(&word.to_lowercase(), valid, definition, now());
+        if cache.unsaved >= SAVE_EVERY{
+            cache.save();
+        }
+    }
+}
+
+// Function to save any answers not yet written to disk, e.g. when the app closes
+pub fn flush(){
+    if let Ok(mut cache) = cache().lock(){
+        if cache.unsaved > 0{
+            cache.save();
+        }
+    }
+}
+
+// Function to mark every word in a list (one per line) as valid; returns how many were added
+pub fn prewarm(word_list: &str) -> usize{
+    let Ok(mut cache) = cache().lock() else { return 0 };
+    let now = now();
+    let mut added = 0;
+    for word in word_list.lines().map(str::trim).filter(|word| !word.is_empty()){
+        cache.insert(&word.to_lowercase(), true, None, now);
+        added += 1;
+    }
+    cache.save();
+    added
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn rejected_words_expire_before_real_ones(){
+        let mut cache = DictionaryCache::default();
+        cache.insert("cat", true, None, 1000);
+        cache.insert("xqz", false, None, 1000);
+        let after_a_day = 1000 + INVALID_TTL.as_secs();
+        assert!(cache.get("cat", after_a_day).is_some_and(|entry| entry.valid));
+        assert!(cache.get("xqz", after_a_day - 1).is_some_and(|entry| !entry.valid));
+        assert!(cache.get("xqz", after_a_day).is_none());
+        assert!(cache.get("cat", 1000 + VALID_TTL.as_secs()).is_none());
+    }
+
+    #[test]
+    fn oldest_entries_are_dropped_over_the_cap(){
+        let mut cache = DictionaryCache::default();
+        for i in 0..=MAX_ENTRIES as u64{
+            cache.insert(&format!("word{}", i), true, None, 1000 + i);
+        }
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+        assert!(cache.get("word0", 2000).is_none());
+        assert!(cache.get("word1", 2000).is_some());
+        assert!(cache.get(&format!("word{}", MAX_ENTRIES), 2000 + MAX_ENTRIES as u64).is_some());
+    }
+
+    #[test]
+    fn expired_entries_are_dropped_before_old_fresh_ones(){
+        let mut cache = DictionaryCache::default();
+        cache.insert("stale", false, None, 0);
+        for i in 0..MAX_ENTRIES as u64{
+            cache.insert(&format!("word{}", i), true, None, INVALID_TTL.as_secs() + i);
+        }
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+        assert!(!cache.entries.contains_key("stale"));
+        assert!(cache.entries.contains_key("word0"));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::dictionary;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Constants for scoring how hard a word is to unscramble
+static UNKNOWN_ZIPF: f32 = 3.0;    // Words missing from the word list (most API words) are assumed to be uncommon
+static COMMON_ZIPF: f32 = 6.0;     // Words at least this frequent score no difficulty for frequency
+static RARE_ZIPF: f32 = 2.5;       // Words this rare or rarer score full difficulty for frequency
+static FREQUENCY_WEIGHT: f32 = 0.6;
+static LETTER_WEIGHT: f32 = 0.25;
+static AMBIGUITY_WEIGHT: f32 = 0.15;
+static EASY_MAX: f32 = 0.4;        // Scores below this are easy
+static MEDIUM_MAX: f32 = 0.5;      // Scores below this (and not easy) are medium; the rest are hard
+pub static WORDS_PER_LENGTH: u8 = 4; // Correct answers at each word length before words get a letter longer
+static BAND_STEPS: [Band; 4] = [Band::Easy, Band::Medium, Band::Medium, Band::Hard]; // Bands of the words at one length, in order
+
+// Difficulty bands within one word length, from the most familiar words to the most obscure
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Band{
+    #[default]
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl fmt::Display for Band{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        f.write_str(match self{
+            Band::Easy => "easy",
+            Band::Medium => "medium",
+            Band::Hard => "hard",
+        })
+    }
+}
+
+impl FromStr for Band{
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err>{
+        match text.to_lowercase().as_str(){
+            "easy" => Ok(Band::Easy),
+            "medium" => Ok(Band::Medium),
+            "hard" => Ok(Band::Hard),
+            other => Err(format!("unknown difficulty '{}' (easy, medium or hard)", other)),
+        }
+    }
+}
+
+// One step of the progression: words of `length` letters from `band`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Tier{
+    pub length: usize,
+    pub band: Band,
+}
+
+// Function to get the band of the word served at a level: each length starts easy and ends hard
+pub fn band_for_level(level: u8) -> Band{
+    BAND_STEPS[(level.max(1) - 1) as usize % BAND_STEPS.len()]
+}
+
+impl fmt::Display for Tier{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        write!(f, "{} letters, {}", self.length, self.band)
+    }
+}
+
+// Function to get a letter's Scrabble value, used as a measure of how rare the letter is
+fn letter_value(letter: char) -> u32{
+    match letter.to_ascii_lowercase(){
+        'a' | 'e' | 'i' | 'l' | 'n' | 'o' | 'r' | 's' | 't' | 'u' => 1,
+        'd' | 'g' => 2,
+        'b' | 'c' | 'm' | 'p' => 3,
+        'f' | 'h' | 'v' | 'w' | 'y' => 4,
+        'k' => 5,
+        'j' | 'x' => 8,
+        'q' | 'z' => 10,
+        _ => 5, // Letters outside the English alphabet are treated as uncommon
+    }
+}
+
+/*
+The score/1 function rates how hard a word is to unscramble from 0 (easiest) to 1 (hardest). It
+mixes how rarely the word is used, how rare its letters are, and how many other words use the same
+letters; since any real anagram is accepted, a word with several anagrams is easier to solve.
+*/
+pub fn score(word: &str) -> f32{
+    let zipf = dictionary::frequency(word).unwrap_or(UNKNOWN_ZIPF);
+    let frequency = ((COMMON_ZIPF - zipf) / (COMMON_ZIPF - RARE_ZIPF)).clamp(0.0, 1.0);
+
+    let letters = word.chars().count().max(1) as f32;
+    let average_value = word.chars().map(letter_value).sum::<u32>() as f32 / letters;
+    let letter_rarity = ((average_value - 1.0) / 3.0).clamp(0.0, 1.0);
+
+    let ambiguity = 1.0 / dictionary::anagram_count(word).max(1) as f32;
+
+    FREQUENCY_WEIGHT * frequency + LETTER_WEIGHT * letter_rarity + AMBIGUITY_WEIGHT * ambiguity
+}
+
+// Function to get the difficulty band a word falls in
+pub fn band(word: &str) -> Band{
+    match score(word){
+        score if score < EASY_MAX => Band::Easy,
+        score if score < MEDIUM_MAX => Band::Medium,
+        _ => Band::Hard,
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+// How a letter in a guess compares to the letter in the same position of the answer
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum LetterFeedback{
+    Correct,   // Right letter in the right position
+    Misplaced, // Letter is in the answer but somewhere else
+    Absent,    // Letter is not in the answer (or all copies of it are already accounted for)
+}
+
+/*
+The position_feedback/2 function compares a guess to the answer letter by letter.
+Exact matches are marked first so that a repeated letter is only marked misplaced
+as many times as it still appears unmatched in the answer.
+*/
+pub fn position_feedback(guess: &str, answer: &str) -> Vec<LetterFeedback>{
+    let guess_chars: Vec<char> = guess.chars().collect();
+    let mut unmatched: Vec<Option<char>> = answer.chars().map(Some).collect(); // Answer letters not yet used up
+    let mut feedback = vec![LetterFeedback::Absent; guess_chars.len()];
+
+    // Pass 1: letters in the right position
+    for (i, letter) in guess_chars.iter().enumerate(){
+        if unmatched.get(i) == Some(&Some(*letter)){
+            feedback[i] = LetterFeedback::Correct;
+            unmatched[i] = None;
+        }
+    }
+
+    // Pass 2: letters somewhere else in the answer
+    for (i, letter) in guess_chars.iter().enumerate(){
+        if feedback[i] == LetterFeedback::Correct{
+            continue;
+        }
+        if let Some(spare) = unmatched.iter_mut().find(|spare| **spare == Some(*letter)){
+            feedback[i] = LetterFeedback::Misplaced;
+            *spare = None;
+        }
+    }
+    feedback
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use LetterFeedback::*;
+
+    #[test]
+    fn exact_match_is_all_correct(){
+        assert_eq!(position_feedback("cat", "cat"), vec![Correct, Correct, Correct]);
+    }
+
+    #[test]
+    fn repeated_guess_letter_is_misplaced_only_once(){
+        // "apple" has one spare p besides the one in place, so only the first extra p is misplaced
+        assert_eq!(position_feedback("pplpa", "apple"), vec![Misplaced, Correct, Misplaced, Absent, Misplaced]);
+    }
+
+    #[test]
+    fn correct_letters_are_matched_before_misplaced_ones(){
+        // Every e of "geese" is taken by a guess e in the same place, leaving none for the first and fourth
+        assert_eq!(position_feedback("eeeee", "geese"), vec![Absent, Correct, Correct, Absent, Correct]);
+    }
+
+    #[test]
+    fn letters_missing_from_the_answer_are_absent(){
+        assert_eq!(position_feedback("dog", "cat"), vec![Absent, Absent, Absent]);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::i18n::tr;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+// Constants for decoy-letter mode
+static LEVELS_PER_DECOY: usize = 3; // One more decoy every few levels (a full easy-medium-hard run)
+static MAX_DECOYS: usize = 3;
+
+// Ways to play, picked on the start screen
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GameMode{
+    #[default]
+    Classic,        // Unscramble the word
+    DefinitionClue, // Unscramble the word with its definition available as a clue
+    Decoy,          // The rack has extra letters that aren't in the word
+    MissingLetter,  // One rack tile is a blank the player fills with the missing letter
+    Phrase,         // Unscramble an idiom or title of several words
+    Grid,           // Trace words through a grid of letters
+    Chain,          // Each answer starts with the last letter of the one before
+}
+
+impl GameMode{
+    pub const ALL: [GameMode; 7] = [GameMode::Classic, GameMode::DefinitionClue, GameMode::Decoy, GameMode::MissingLetter, GameMode::Phrase, GameMode::Grid, GameMode::Chain];
+
+    // Function to get the key of the mode's name in the message catalogs
+    fn key(&self) -> &'static str{
+        match self{
+            GameMode::Classic => "mode.classic",
+            GameMode::DefinitionClue => "mode.definition_clue",
+            GameMode::Decoy => "mode.decoy",
+            GameMode::MissingLetter => "mode.missing_letter",
+            GameMode::Phrase => "mode.phrase",
+            GameMode::Grid => "mode.grid",
+            GameMode::Chain => "mode.chain",
+        }
+    }
+
+    pub fn label(&self) -> String{
+        tr(self.key())
+    }
+
+    pub fn description(&self) -> String{
+        tr(&format!("{}_description", self.key()))
+    }
+
+    // Function to get how many decoy letters go in the rack at a level; none outside decoy mode
+    pub fn decoys(&self, level: u8) -> usize{
+        match self{
+            GameMode::Decoy => (1 + (level.max(1) as usize - 1) / LEVELS_PER_DECOY).min(MAX_DECOYS),
+            _ => 0,
+        }
+    }
+}
+
+// Function to draw the mode picker on the start screen
+pub fn mode_picker(ui: &mut egui::Ui, mode: &mut GameMode){
+    egui::ComboBox::from_label(tr("mode.label"))
+        .selected_text(mode.label())
+        .show_ui(ui, |ui|{
+            for option in GameMode::ALL{
+                ui.selectable_value(mode, option, option.label()).on_hover_text(option.description());
+            }
+        });
+    ui.weak(mode.description());
+}
@@ -1,9 +1,17 @@
 use std::time::Duration;             // Timer 
+use std::sync::mpsc::{Receiver, TryRecvError}; // Results of background work
 use serde::{Serialize, Deserialize}; // Used to convert to JSON for saving game
-use std::sync::mpsc::Receiver;
-use std::fs::File;
-use std::io::{BufWriter, Write};
-use crate::api;                      // Use dictionary API
+use crate::api::{self, WordCheck};   // Use dictionary API
+use crate::prefetch::WordPrefetcher; // Upcoming words fetched in the background
+use crate::word_history;             // Words served recently, so they aren't repeated
+use crate::word_filter;              // Words the player doesn't want served
+use crate::difficulty::{self, Band, Tier}; // How hard the words being served are
+use crate::language::{self, Language}; // Language words are played in
+use crate::game_mode::GameMode;      // Way of playing picked on the start screen
+use crate::phrase;                   // Multi-word puzzles
+use crate::grid::{self, LetterGrid, TraceWords}; // Letter grid mode
+
+pub static CLUE_BONUS: u32 = 10;      // Extra points in definition-clue mode for solving without the clue
 
 // Structure to represent the game state with serialize and deserialize to convert to JSON to be stored for later
 #[derive(Serialize, Deserialize)]
@@ -11,12 +19,36 @@ pub struct GameState {
     pub score: u32,               // Player score
     pub time_alotted: Duration,      // Time left
     pub word_length: usize,       // Length of word to unscamble
+    #[serde(default)]
+    pub band: Band,               // Difficulty band of words at this length
     pub correct_answers: u8,      // Correct answer (determines word length)
     pub original_word: String,    // Original word (determines correct answer)
     pub scrambled_word: String,   // Scrambled word (determines order of letters from orig word presented to player)
     pub restore_scrambled: String,
-    pub level: u8,                // Level (increases for every 4 words)
-    pub start: bool
+    pub level: u8,                // Level (+1 for every correct answer; words get 1 letter longer every 4 levels)
+    pub start: bool,
+    #[serde(default)]
+    pub wrong_guesses: u8,        // Wrong guesses made on the current word
+    #[serde(default)]
+    pub pack: Option<String>,     // Name of the word pack being played, None for random words
+    #[serde(default)]
+    pub language: Language,       // Language of the words served and the dictionary they are checked in
+    #[serde(default)]
+    pub mode: GameMode,
+    #[serde(default)]
+    pub clue_revealed: bool,      // Definition clue shown for the current word (forfeits the clue bonus)
+    #[serde(default)]
+    pub grid: Option<LetterGrid>, // Letter grid being played in grid mode
+    #[serde(default = "grid::default_size")]
+    pub grid_size: usize,         // Rows and columns of the letter grid
+    #[serde(default)]
+    pub chain_letter: Option<char>, // Letter the next answer must start with in word-chain mode
+    #[serde(default)]
+    pub chain_length: u32,        // Words chained in a row; multiplies the score of each one
+    #[serde(skip)]
+    pub prefetcher: Option<WordPrefetcher>, // Started with the first word
+    #[serde(skip)]
+    pub word_receiver: Option<Receiver<Option<(String, String)>>>, // Word being fetched when none was prefetched
 }
 
 impl GameState {
@@ -25,18 +57,50 @@ impl GameState {
             score: 0,                              // Score starts at 0
             time_alotted: Duration::from_secs(60),    // Start with 60 sec on clock
             word_length: 4,                        // Start by unscrambling 4 letter words
+            band: Band::Easy,                      // Start with the most familiar words of each length
             correct_answers: 0,                    // Start at correct_answers 1 (+1 correct_answers every 4 words)
             original_word: String::new(),          // Initiate new word
             scrambled_word: String::new(),         // Scramble word
             restore_scrambled: String::new(),       // scrambled word for restoring when user gets it wrong
-            level: 1,                              // Start at level 1 (+1 level every 4 right answers)
-            start: true}
+            level: 1,                              // Start at level 1 (+1 level every right answer)
+            start: true,
+            wrong_guesses: 0,
+            pack: None,
+            language: Language::English,
+            mode: GameMode::Classic,
+            clue_revealed: false,
+            grid: None,
+            grid_size: grid::default_size(),
+            chain_letter: None,
+            chain_length: 0,
+            prefetcher: None,
+            word_receiver: None}
+    }
+
+    // Function to fetch a word `wanted` accepts on a background thread; receive_word picks it up without holding up the window
+    fn fetch_word(&mut self, tier: Tier, wanted: impl Fn(&str) -> bool + Send + 'static) {
+        let (sender, receiver) = std::sync::mpsc::channel(); //Send to, and receive from the API
+        std::thread::spawn(move || { let _ = sender.send(api::next_word(tier, wanted)); // API word, or local word when offline
+        });
+        self.word_receiver = Some(receiver);
+    }
+
+    // Function to get the progression step words are currently served from
+    pub fn tier(&self) -> Tier {
+        Tier{ length: self.word_length, band: self.band }
+    }
+
+    // Function to get the progression step the next correct answer moves to
+    pub fn next_tier(&self) -> Tier {
+        let length = if self.level % difficulty::WORDS_PER_LENGTH == 0 { self.word_length.saturating_add(1) } else { self.word_length };
+        Tier{ length, band: difficulty::band_for_level(self.level.saturating_add(1)) }
     }
 }
 
 pub trait ValidateAnswer{
-    fn validate_answer(&mut self, input: String) -> bool;
-    fn can_form_anagram(input: String, original: String) -> bool;
+    fn check_answer(&self, input: String) -> Receiver<(String, WordCheck)>; // Check a guess on a background thread
+    fn apply_check(&mut self, input: String, check: WordCheck) -> WordCheck; // Score a checked guess; Unverified ones aren't scored
+    fn can_form_anagram(input: String, rack: String) -> bool;
 }
 pub trait UpdateGameVariables{
     fn increment_word_length(&mut self) -> &mut Self;
@@ -44,22 +108,33 @@ pub trait UpdateGameVariables{
     fn incorrect_answer(&mut self) -> &mut Self;
     fn set_word(&mut self, scrambled: String, original: String);
     fn get_new_word(&mut self);
+    fn receive_word(&mut self) -> bool; // Use a word fetched in the background once it arrives; true if the word changed
+    fn skip_word(&mut self);
+    fn add_time(&mut self, seconds: i64) -> &mut Self;
+    fn set_level(&mut self, level: u8) -> &mut Self;
+    fn set_word_length(&mut self, length: usize) -> &mut Self;
+    fn set_band(&mut self, band: Band) -> &mut Self;
+    fn end_game(&mut self) -> &mut Self;
 }
 
 impl UpdateGameVariables for GameState{
-    // Function to increment the word length by 1 letter every 4 correct answers
+    // Function to move one step up the progression: 1 letter longer every 4 correct answers, harder words within each length
     fn increment_word_length(&mut self) -> &mut Self {
-        if self.level % 4 == 0 {
-            self.word_length += 1;
-        }
-        self.level += 1;
+        let next = self.next_tier();
+        self.word_length = next.length;
+        self.band = next.band;
+        self.level = self.level.saturating_add(1); // The dev console can set any level up to 255
         self
     }
 
     // Function to handle when player inputs correct answer
     fn correct_answer(&mut self) -> &mut Self {
-        self.score += 10;
-        self.time_alotted += Duration::from_secs(5);
+        let multiplier = if self.mode == GameMode::Chain { self.chain_length.max(1) } else { 1 };
+        self.score += 10 * self.original_word.split_whitespace().count().max(1) as u32 * multiplier; // A phrase scores once, for all of its words
+        if self.mode == GameMode::DefinitionClue && !self.clue_revealed {
+            self.score += CLUE_BONUS;
+        }
+        self.time_alotted = self.time_alotted.saturating_add(Duration::from_secs(5));
         self
     }
 
@@ -74,63 +149,221 @@ impl UpdateGameVariables for GameState{
     }
     
     fn set_word(&mut self, scrambled: String, original: String){
+        let (mut scrambled, original) = (language::normalize(&scrambled), language::normalize(&original));
+        let decoys = self.mode.decoys(self.level);
+        if decoys > 0 {
+            scrambled = api::add_decoys(&scrambled, &original, decoys); // Decoy mode: extra letters the answer doesn't use
+        }
+        if self.mode == GameMode::MissingLetter {
+            scrambled = api::add_blank(&scrambled);
+        }
+        word_history::record(&original);
+        self.wrong_guesses = 0;
+        self.clue_revealed = false;
         self.restore_scrambled = scrambled.clone();
         self.scrambled_word = scrambled;
         self.original_word = original;
     }
 
     fn get_new_word (&mut self){
-        let (sender, receiver) = std::sync::mpsc::channel(); //Send to, and receive from the API
-        let word_length = self.word_length;
+        // Use a prefetched word when one is ready; only go to the network when the queue is empty
+        let tier = self.tier();
+        self.word_receiver = None; // A word still being fetched for an earlier request isn't wanted any more
+        if self.mode == GameMode::Grid {
+            self.new_grid(); // One grid lasts the whole round
+            return;
+        }
+        if let Some(letter) = self.chain_letter.filter(|_| self.mode == GameMode::Chain) {
+            return self.fetch_word(tier, move |word| language::starts_with_letter(word, letter)); // Prefetched words can't be chosen by first letter
+        }
+        if self.mode == GameMode::Phrase {
+            match phrase::next_phrase(tier) {
+                Some(original) => self.set_word(api::scramble(&phrase::letters(&original)), original),
+                None => log::error!(target: "game", "no phrase available: word_length={}", tier.length),
+            }
+            return;
+        }
+        let upcoming = self.next_tier();
+        let prefetcher = self.prefetcher.get_or_insert_with(|| WordPrefetcher::start(tier, upcoming));
+        prefetcher.keep_ready(tier, upcoming);
+        while let Some((scrambled, original)) = prefetcher.take(tier) {
+            if word_filter::allows(&original) && !word_history::is_recent(&original) { // Filter changed or served since it was fetched; try the next one
+                self.set_word(scrambled, original);
+                return;
+            }
+        }
 
-        std::thread::spawn(move || { let result = 
-                                     match api::get_scrambled_word(word_length) {
-                                         Some((scrambled_word, original_word)) => (scrambled_word, original_word),
-                                         None => ("default_scrambled".to_string(), "default_original".to_string())};
-                                     let _ = sender.send(result); // result = the scrambled word
-        });
+        self.fetch_word(tier, |_| true);
+    }
+
+    fn receive_word(&mut self) -> bool {
+        let Some(receiver) = &self.word_receiver else { return false };
+        let received = match receiver.try_recv() {
+            Err(TryRecvError::Empty) => return false,
+            result => result,
+        };
+        self.word_receiver = None;
+        match received {
+            Ok(Some((scrambled, original))) => { self.set_word(scrambled, original); true },
+            Ok(None) if self.chain_letter.is_some() => { // No word to chain onto; the next answer can start with any letter
+                log::warn!(target: "game", "no word starts with chain letter: word_length={}", self.word_length);
+                self.chain_letter = None;
+                self.get_new_word();
+                self.word_receiver.is_none() },
+            Ok(None) => { log::error!(target: "game", "no word available: word_length={} band={}", self.word_length, self.band); false }, // Keep the current word
+            Err(e) => { log::error!(target: "game", "word fetch thread failed: {}", e); false },
+        }
+    }
+
+    // Function to give up on the current word without scoring it, which breaks a word chain
+    fn skip_word(&mut self){
+        self.chain_length = 0;
+        self.chain_letter = None;
+        self.get_new_word();
+    }
+
+    // Function to add (or with a negative number, remove) seconds from the clock
+    fn add_time(&mut self, seconds: i64) -> &mut Self {
+        let change = Duration::from_secs(seconds.unsigned_abs());
+        self.time_alotted = if seconds >= 0 { self.time_alotted.saturating_add(change) } else { self.time_alotted.saturating_sub(change) };
+        self
+    }
 
-        match receiver.recv(){
-            Ok((scrambled, original)) => self.set_word(scrambled, original),
-            Err(e) => self.set_word("ERROR".into(), "ERROR".into()),
+    // Function to jump to a level; word length is not changed
+    fn set_level(&mut self, level: u8) -> &mut Self {
+        self.level = level.max(1);
+        if let Some(prefetcher) = &self.prefetcher {
+            prefetcher.keep_ready(self.tier(), self.next_tier());
         }
+        self
+    }
+
+    // Function to change the length of words fetched from now on
+    fn set_word_length(&mut self, length: usize) -> &mut Self {
+        self.word_length = length.max(1);
+        if let Some(prefetcher) = &self.prefetcher {
+            prefetcher.keep_ready(self.tier(), self.next_tier());
+        }
+        self
+    }
+
+    // Function to change the difficulty band of words fetched from now on
+    fn set_band(&mut self, band: Band) -> &mut Self {
+        self.band = band;
+        if let Some(prefetcher) = &self.prefetcher {
+            prefetcher.keep_ready(self.tier(), self.next_tier());
+        }
+        self
+    }
+
+    // Function to run the clock out so the game ends on the next frame
+    fn end_game(&mut self) -> &mut Self {
+        self.time_alotted = Duration::ZERO;
+        self
     }
 }
 
 impl ValidateAnswer for GameState{
-    fn validate_answer(&mut self, input: String) -> bool{
+    fn check_answer(&self, input: String) -> Receiver<(String, WordCheck)>{
         let (sender, receiver) = std::sync::mpsc::channel();
         let original_word = self.original_word.clone();
+        let rack = self.restore_scrambled.clone(); // Every tile dealt for this word, decoys included
+        let chain_letter = self.chain_letter.filter(|_| self.mode == GameMode::Chain);
         // Spawn a background thread
         std::thread::spawn(move || {
-            let is_exact_match = input == original_word;
-            let is_valid_anagram = api::is_valid_word(&input) && GameState::can_form_anagram(input.clone(), original_word);
+            // Exact matches need no lookup; other anagrams are checked with the dictionary, word by word for a phrase
+            let input = language::normalize(&input);
+            let answer = phrase::letters(&language::normalize(&original_word));
+            let check = if input == answer { WordCheck::Valid }
+                        else if input.chars().count() != answer.chars().count()
+                            || !GameState::can_form_anagram(input.clone(), rack)
+                            || chain_letter.is_some_and(|letter| !language::starts_with_letter(&input, letter)) { WordCheck::Invalid } // Word chain mode: must continue the chain
+                        else { api::check_phrase(&phrase::split(&input, &original_word)) };
 
-            let _ = sender.send((input, is_exact_match || is_valid_anagram)); //Return true if exact match, or anagram
+            let _ = sender.send((input, check));
         });
-        match receiver.recv() {
-            Ok((_, is_valid)) =>{
-                if is_valid { self.correct_answer() 
-                                .increment_word_length()
-                                .get_new_word();
-                              true}
-
-                else { self.incorrect_answer();
-                    self.scrambled_word = self.restore_scrambled.clone();
-                    println!("{}", &self.original_word);
-                       false}
-            }
+        receiver
+    }
+
+    fn apply_check(&mut self, input: String, check: WordCheck) -> WordCheck{
+        match check {
+            WordCheck::Valid =>{
+                if self.mode == GameMode::Chain {
+                    self.chain_length += 1;
+                    self.chain_letter = input.chars().last(); // The player's word sets the next letter, even if it isn't the served one
+                    log::info!(target: "game", "word chained: chain_length={}", self.chain_length);
+                }
+                self.correct_answer() 
+                    .increment_word_length()
+                    .get_new_word();
+                log::info!(target: "game", "correct guess: level={} word_length={} band={} score={}", self.level, self.word_length, self.band, self.score);}
 
-            Err(e) => {eprint!("Error validating guess: {}", e);
-                       false}}
+            WordCheck::Invalid =>{
+                self.incorrect_answer();
+                self.wrong_guesses = self.wrong_guesses.saturating_add(1);
+                self.scrambled_word = self.restore_scrambled.clone();
+                log::info!(target: "game", "wrong guess: level={} wrong_guesses={} score={}", self.level, self.wrong_guesses, self.score);}
+
+            WordCheck::Unverified =>{ // Couldn't check the word, so don't penalize the player for it
+                log::warn!(target: "game", "guess could not be verified offline: level={}", self.level);}}
+        check
     }
 
-    fn can_form_anagram(input: String, original: String) -> bool {
-        let mut input_chars: Vec<char> = input.chars().collect(); // Convert input to a vector of characters
-        let mut original_chars: Vec<char> = original.chars().collect(); // Convert original to a vector of characters
-        input_chars.sort_unstable(); // Sort the input characters
-        original_chars.sort_unstable(); // Sort the original characters
-        input_chars == original_chars // Check if the sorted input characters match the sorted original characters
+    // Function to check that every letter of the input can be taken from the rack (a sub-multiset, so decoys can be left over);
+    // a letter the rack doesn't have can use up a blank tile instead
+    fn can_form_anagram(input: String, rack: String) -> bool {
+        let mut rack_chars: Vec<char> = language::normalize(&rack).chars().collect(); // Convert rack to a vector of characters (NFC, so accents match however they were typed)
+        language::normalize(&input).chars().all(|letter| { // Use up one rack tile per input letter
+            match rack_chars.iter().position(|tile| *tile == letter).or_else(|| rack_chars.iter().position(|tile| *tile == api::BLANK)) {
+                Some(index) => { rack_chars.swap_remove(index); true }
+                None => false,
+            }
+        })
     }
 }
 
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn forms(input: &str, rack: &str) -> bool{
+        GameState::can_form_anagram(input.into(), rack.into())
+    }
+
+    #[test]
+    fn words_grow_a_letter_every_four_levels_from_easy_to_hard(){
+        use Band::*;
+        let mut state = GameState::new();
+        let mut steps = vec![(state.level, state.word_length, state.band)];
+        while state.level < 9 {
+            state.increment_word_length();
+            steps.push((state.level, state.word_length, state.band));
+        }
+        assert_eq!(steps, vec![
+            (1, 4, Easy), (2, 4, Medium), (3, 4, Medium), (4, 4, Hard),
+            (5, 5, Easy), (6, 5, Medium), (7, 5, Medium), (8, 5, Hard),
+            (9, 6, Easy)]);
+    }
+
+    #[test]
+    fn anagram_uses_each_rack_tile_once(){
+        assert!(forms("tac", "cat"));
+        assert!(!forms("tact", "cat"));
+    }
+
+    #[test]
+    fn decoy_tiles_can_be_left_over(){
+        assert!(forms("cat", "xcatq"));
+        assert!(!forms("cab", "xcatq"));
+    }
+
+    #[test]
+    fn blank_tile_stands_in_for_one_missing_letter(){
+        let rack = format!("ca{}", api::BLANK);
+        assert!(forms("cat", &rack));
+        assert!(forms("cab", &rack));
+        assert!(!forms("cabs", &rack));
+        assert!(!forms("cbb", &rack));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::api::{self, WordCheck};
+use crate::game_state::{GameState, UpdateGameVariables};
+use crate::{dictionary, language, word_filter};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::Receiver;
+
+// Constants for grid mode
+pub static MIN_SIZE: usize = 3;
+pub static MAX_SIZE: usize = 6;
+static DEFAULT_SIZE: usize = 4;
+pub static MIN_WORD_LENGTH: usize = 3; // Shorter traces are never words
+static POINTS_PER_LETTER: u32 = 5;     // A shortest word scores this much, and each extra letter adds as much again
+
+pub fn default_size() -> usize{
+    DEFAULT_SIZE
+}
+
+// Struct for an N×N grid of letters, the words found in it so far and every word the local word list can trace in it
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LetterGrid{
+    pub size: usize,
+    pub letters: Vec<char>, // Row by row, `size` letters per row
+    pub found: Vec<String>, // Words the player traced, in the order found
+    pub possible: Vec<String>, // Words from the local word list that can be traced, longest first
+}
+
+impl LetterGrid{
+    /*
+    The generate/1 function fills a grid with letters. Each letter is taken from a random word of
+    the local word list, so letters turn up about as often as they do in the language being played
+    and grids have enough vowels to make words. The grid is then solved against the word list.
+    */
+    pub fn generate(size: usize) -> Self{
+        let size = size.clamp(MIN_SIZE, MAX_SIZE);
+        let words = dictionary::all_words();
+        let mut rng = rand::thread_rng();
+        let letters = (0..size * size)
+            .map(|_| words.choose(&mut rng).and_then(|word| word.chars().collect::<Vec<_>>().choose(&mut rng).copied()).unwrap_or('e'))
+            .collect();
+        let mut grid = Self{ size, letters, found: Vec::new(), possible: Vec::new() };
+        grid.possible = grid.solve();
+        log::info!(target: "game", "letter grid generated: size={} possible_words={}", size, grid.possible.len());
+        grid
+    }
+
+    // Function to check whether two cells touch, across or diagonally
+    pub fn adjacent(&self, a: usize, b: usize) -> bool{
+        let (row_a, column_a) = (a / self.size, a % self.size);
+        let (row_b, column_b) = (b / self.size, b % self.size);
+        a != b && row_a.abs_diff(row_b) <= 1 && column_a.abs_diff(column_b) <= 1
+    }
+
+    // Function to spell the word along a path of cells
+    pub fn word(&self, path: &[usize]) -> String{
+        path.iter().filter_map(|cell| self.letters.get(*cell)).collect()
+    }
+
+    // Function to check whether a word can be traced through adjacent cells, using each cell at most once
+    fn can_trace(&self, word: &[char]) -> bool{
+        fn extend(grid: &LetterGrid, word: &[char], path: &mut Vec<usize>) -> bool{
+            let Some(next) = word.get(path.len()) else { return true };
+            for cell in 0..grid.letters.len(){
+                let fits = grid.letters[cell] == *next && !path.contains(&cell) && path.last().is_none_or(|last| grid.adjacent(*last, cell));
+                if fits{
+                    path.push(cell);
+                    if extend(grid, word, path){
+                        return true;
+                    }
+                    path.pop();
+                }
+            }
+            false
+        }
+        extend(self, word, &mut Vec::with_capacity(word.len()))
+    }
+
+    // Function to find every word of the local word list that can be traced in the grid and passes the content filter
+    fn solve(&self) -> Vec<String>{
+        let mut possible: Vec<String> = dictionary::all_words().into_iter()
+            .filter(|word| (MIN_WORD_LENGTH..=self.letters.len()).contains(&word.chars().count()) && word_filter::allows(word))
+            .filter(|word| self.can_trace(&word.chars().collect::<Vec<_>>()))
+            .map(str::to_string)
+            .collect();
+        possible.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then_with(|| a.cmp(b)));
+        possible
+    }
+}
+
+// Function to score a found word; longer words are worth more
+pub fn points(word: &str) -> u32{
+    (word.chars().count().saturating_sub(MIN_WORD_LENGTH) as u32 + 1) * POINTS_PER_LETTER
+}
+
+/*
+The extend_path/4 function adds a cell to the path being traced. A cell next to the end of the path
+is added; going back onto the cell before the end undoes the last step, so a drag can be retraced.
+Any other cell is ignored while dragging, or starts a new path when it was just pressed.
+*/
+pub fn extend_path(grid: &LetterGrid, path: &mut Vec<usize>, cell: usize, pressed: bool){
+    if path.last() == Some(&cell){
+        return;
+    }
+    if path.len() >= 2 && path[path.len() - 2] == cell{
+        path.pop();
+    }
+    else if !path.contains(&cell) && path.last().is_none_or(|last| grid.adjacent(*last, cell)){
+        path.push(cell);
+    }
+    else if pressed{
+        *path = vec![cell];
+    }
+}
+
+// Playing the letter grid
+pub trait TraceWords{
+    fn new_grid(&mut self) -> &mut Self;                       // Deal a fresh grid
+    fn check_path(&self, path: &[usize]) -> Option<Receiver<(String, WordCheck)>>; // Check a traced word; None if it can't be submitted
+    fn score_word(&mut self, word: String, check: WordCheck) -> Option<WordCheck>;  // Score a checked word; None if it was already found
+}
+
+impl TraceWords for GameState{
+    fn new_grid(&mut self) -> &mut Self{
+        self.grid = Some(LetterGrid::generate(self.grid_size));
+        self
+    }
+
+    /*
+    The check_path/1 function checks the word along a traced path. Traces that are too short or
+    repeat a found word are ignored. Words the grid was solved with are accepted straight away;
+    anything else is looked up in the dictionary on a background thread, like a guess.
+    */
+    fn check_path(&self, path: &[usize]) -> Option<Receiver<(String, WordCheck)>>{
+        let grid = self.grid.as_ref()?;
+        let word = language::normalize(&grid.word(path));
+        if word.chars().count() < MIN_WORD_LENGTH || grid.found.contains(&word){
+            return None;
+        }
+        let (sender, receiver) = std::sync::mpsc::channel();
+        if grid.possible.contains(&word){
+            let _ = sender.send((word, WordCheck::Valid));
+        } else {
+            std::thread::spawn(move || { let check = api::check_word(&word); let _ = sender.send((word, check)); });
+        }
+        Some(receiver)
+    }
+
+    fn score_word(&mut self, word: String, check: WordCheck) -> Option<WordCheck>{
+        let grid = self.grid.as_mut()?;
+        if grid.found.contains(&word){ // Traced again while the first trace was being checked
+            return None;
+        }
+        match check{
+            WordCheck::Valid => {
+                grid.found.push(word.clone());
+                self.score += points(&word);
+                log::info!(target: "game", "grid word found: word_length={} found={} score={}", word.chars().count(), grid.found.len(), self.score);},
+            WordCheck::Invalid => {
+                self.incorrect_answer();
+                log::info!(target: "game", "grid word rejected: score={}", self.score);},
+            WordCheck::Unverified => {
+                log::warn!(target: "game", "grid word could not be verified offline");},
+        }
+        Some(check)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    // c a t
+    // o d e
+    // g s r
+    fn grid() -> LetterGrid{
+        LetterGrid{ size: 3, letters: "catodegsr".chars().collect(), found: Vec::new(), possible: Vec::new() }
+    }
+
+    fn traces(word: &str) -> bool{
+        grid().can_trace(&word.chars().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn cells_touch_across_and_diagonally(){
+        let grid = grid();
+        assert!(grid.adjacent(0, 1) && grid.adjacent(0, 3) && grid.adjacent(0, 4));
+        assert!(grid.adjacent(4, 8) && grid.adjacent(2, 4));
+        assert!(!grid.adjacent(0, 2) && !grid.adjacent(0, 8));
+        assert!(!grid.adjacent(2, 3)); // End of one row and start of the next
+        assert!(!grid.adjacent(4, 4));
+    }
+
+    #[test]
+    fn words_follow_touching_cells_once_each(){
+        assert!(traces("cat"));
+        assert!(traces("dog"));
+        assert!(traces("code"));
+        assert!(!traces("tag"));  // t and g don't touch
+        assert!(!traces("dad"));  // The grid has one d, and it can't be used twice
+    }
+
+    #[test]
+    fn dragging_back_undoes_the_last_step(){
+        let grid = grid();
+        let mut path = Vec::new();
+        for cell in [0, 1, 4]{
+            extend_path(&grid, &mut path, cell, false);
+        }
+        assert_eq!(path, vec![0, 1, 4]);
+        extend_path(&grid, &mut path, 1, false);
+        assert_eq!(path, vec![0, 1]);
+        extend_path(&grid, &mut path, 1, false); // Staying on the end cell changes nothing
+        assert_eq!(path, vec![0, 1]);
+    }
+
+    #[test]
+    fn far_cell_is_ignored_while_dragging_and_restarts_when_pressed(){
+        let grid = grid();
+        let mut path = vec![0, 1, 2];
+        extend_path(&grid, &mut path, 6, false);
+        assert_eq!(path, vec![0, 1, 2]);
+        extend_path(&grid, &mut path, 0, false); // Already used, and not the cell before the end
+        assert_eq!(path, vec![0, 1, 2]);
+        extend_path(&grid, &mut path, 6, true);
+        assert_eq!(path, vec![6]);
+    }
+}
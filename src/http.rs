@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+// Constants for how long to wait on the network and how hard to retry
+static CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+static REQUEST_TIMEOUT: Duration = Duration::from_secs(5);  // Whole request, including reading the body
+static MAX_ATTEMPTS: u32 = 3;
+static BASE_BACKOFF: Duration = Duration::from_millis(200); // Doubled after every failed attempt
+static MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+// Whether the last request reached a server; drives the offline indicator
+static ONLINE: AtomicBool = AtomicBool::new(true);
+
+// Ways a request can fail, so callers can tell "no such word" apart from "couldn't ask"
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError{
+    Timeout,          // Server took too long to answer
+    Offline(String),  // Couldn't reach the server at all (DNS, refused connection, ...)
+    Status(u16),      // Server answered with an error status
+    Parse(String),    // Server answered but the body wasn't what we expected
+}
+
+impl fmt::Display for ApiError{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self{
+            ApiError::Timeout => write!(f, "request timed out"),
+            ApiError::Offline(reason) => write!(f, "network unavailable: {}", reason),
+            ApiError::Status(code) => write!(f, "server returned status {}", code),
+            ApiError::Parse(reason) => write!(f, "unexpected response: {}", reason),
+        }
+    }
+}
+
+impl ApiError{
+    // Function to check if trying the same request again could help
+    fn is_retryable(&self) -> bool{
+        match self{
+            ApiError::Timeout | ApiError::Offline(_) => true,
+            ApiError::Status(code) => *code == 429 || *code >= 500,
+            ApiError::Parse(_) => false,
+        }
+    }
+
+    // Function to check if the error means we never heard from the server
+    pub fn is_network(&self) -> bool{
+        matches!(self, ApiError::Timeout | ApiError::Offline(_))
+    }
+}
+
+impl From<ureq::Error> for ApiError{
+    fn from(error: ureq::Error) -> Self{
+        match error{
+            ureq::Error::Status(code, _) => ApiError::Status(code),
+            ureq::Error::Transport(transport) => {
+                let source = transport.source();
+                let timed_out = source.and_then(|source| source.downcast_ref::<io::Error>())
+                    .is_some_and(|e| matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock));
+                if timed_out{
+                    return ApiError::Timeout;
+                }
+                // Built from the parts of the error only: its Display starts with the URL, which can hold the word being looked up
+                let mut reason = transport.kind().to_string();
+                if let Some(message) = transport.message(){
+                    reason = format!("{}: {}", reason, message);
+                }
+                if let Some(source) = source{
+                    reason = format!("{}: {}", reason, source);
+                }
+                ApiError::Offline(reason)
+            }
+        }
+    }
+}
+
+// Function to check if the most recent request reached its server
+pub fn is_online() -> bool{
+    ONLINE.load(Ordering::Relaxed)
+}
+
+// Shared agent so every request gets the same timeouts and connection reuse
+fn agent() -> &'static ureq::Agent{
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build())
+}
+
+/*
+The get/1 function sends a GET request and returns the response body.
+Timeouts, connection failures, 429 and 5xx answers are retried up to MAX_ATTEMPTS times,
+waiting BASE_BACKOFF, then twice that, and so on between attempts. Other error statuses
+(like 404 for a word that isn't in the dictionary) are returned straight away. While the last
+request couldn't reach its server the game is offline, and a failed request isn't retried.
+*/
+pub fn get(url: &str) -> Result<String, ApiError>{
+    let mut backoff = BASE_BACKOFF;
+    let mut attempt = 1;
+    let was_online = is_online();
+    loop{
+        let result = agent().get(url).call()
+            .map_err(ApiError::from)
+            .and_then(|response|{
+                let mut body = String::new();
+                response.into_reader().take(MAX_BODY_BYTES).read_to_string(&mut body)
+                    .map_err(|e| ApiError::Parse(e.to_string()))?;
+                Ok(body)
+            });
+
+        ONLINE.store(!matches!(&result, Err(e) if e.is_network()), Ordering::Relaxed);
+        match result{
+            Err(e) if e.is_retryable() && was_online && attempt < MAX_ATTEMPTS => {
+                log::debug!(target: "http", "retrying request: attempt={} error={}", attempt, e);
+                thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;},
+            _ => return result,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::json_dir;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Mutex, OnceLock};
+
+// Constants for where message catalogs come from
+static LOCALE_DIRECTORY: &str = "locales";                        // One JSON catalog per language, e.g. locales/fr.json
+static FALLBACK_LOCALE: &str = "en";
+static FALLBACK_CATALOG: &str = include_str!("../locales/en.json"); // Built in so every key has text even without the folder
+
+// A message is plain text, or one text per plural category ("one", "other", ...)
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+enum Message{
+    Text(String),
+    Plural(HashMap<String, String>),
+}
+
+// Struct for one language's UI text, keyed by message id (e.g. "game_over.title")
+#[derive(Deserialize, Clone, Debug)]
+struct Catalog{
+    language_name: String, // Shown in the interface language picker
+    messages: HashMap<String, Message>,
+}
+
+/*
+The catalogs/0 function reads every catalog in the locales folder once. English is always present:
+the built-in copy is used when the folder has no en.json, and it supplies any message a translation
+leaves out. Developer tools (debug overlay and console) are not translated.
+*/
+fn catalogs() -> &'static HashMap<String, Catalog>{
+    static CATALOGS: OnceLock<HashMap<String, Catalog>> = OnceLock::new();
+    CATALOGS.get_or_init(||{
+        let mut catalogs = HashMap::new();
+        match serde_json::from_str::<Catalog>(FALLBACK_CATALOG){
+            Ok(catalog) => { catalogs.insert(FALLBACK_LOCALE.to_string(), catalog); }
+            Err(e) => log::error!(target: "i18n", "built-in catalog is invalid: error={}", e),
+        }
+        for (path, catalog) in json_dir::load_json_dir::<Catalog>(LOCALE_DIRECTORY){
+            if let Some(code) = path.file_stem().and_then(|stem| stem.to_str()).map(str::to_lowercase){
+                catalogs.insert(code, catalog);
+            }
+        }
+        catalogs
+    })
+}
+
+// Locale the UI is currently shown in
+fn locale() -> &'static Mutex<String>{
+    static LOCALE: OnceLock<Mutex<String>> = OnceLock::new();
+    LOCALE.get_or_init(|| Mutex::new(FALLBACK_LOCALE.to_string()))
+}
+
+// Function to switch the UI language; unknown codes fall back to English
+pub fn set_locale(code: &str){
+    let code = if catalogs().contains_key(code) { code } else { FALLBACK_LOCALE };
+    if let Ok(mut locale) = locale().lock(){
+        if *locale != code{
+            log::info!(target: "i18n", "interface language changed: code={}", code);
+            *locale = code.to_string();
+        }
+    }
+}
+
+// Function to list the available UI languages as (code, name), sorted by name
+pub fn locales() -> Vec<(String, String)>{
+    let mut locales: Vec<(String, String)> = catalogs().iter().map(|(code, catalog)| (code.clone(), catalog.language_name.clone())).collect();
+    locales.sort_by(|a, b| a.1.cmp(&b.1));
+    locales
+}
+
+// Function to get the name of a UI language from its code
+pub fn language_name(code: &str) -> String{
+    catalogs().get(code).map_or(code.to_string(), |catalog| catalog.language_name.clone())
+}
+
+// Function to pick the plural category of a count; French uses the singular for 0 as well as 1
+fn plural_category(code: &str, count: u64) -> &'static str{
+    match (code, count){
+        ("fr", 0 | 1) => "one",
+        (_, 1) => "one",
+        _ => "other",
+    }
+}
+
+// Function to find a message in the current language, falling back to English and then to the key itself
+fn lookup(key: &str, count: Option<u64>) -> String{
+    let code = locale().lock().map_or(FALLBACK_LOCALE.to_string(), |locale| locale.clone());
+    let message = catalogs().get(&code).and_then(|catalog| catalog.messages.get(key)).map(|message| (code.as_str(), message))
+        .or_else(|| catalogs().get(FALLBACK_LOCALE).and_then(|catalog| catalog.messages.get(key)).map(|message| (FALLBACK_LOCALE, message)));
+    match message{
+        Some((_, Message::Text(text))) => text.clone(),
+        Some((code, Message::Plural(forms))) => {
+            let category = plural_category(code, count.unwrap_or(0));
+            forms.get(category).or_else(|| forms.get("other")).cloned().unwrap_or_else(|| key.to_string())},
+        None => key.to_string(),
+    }
+}
+
+// Function to fill {name} placeholders in a message
+fn fill(mut text: String, args: &[(&str, &dyn Display)]) -> String{
+    for (name, value) in args{
+        text = text.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    text
+}
+
+// Function to get a message in the current UI language
+pub fn tr(key: &str) -> String{
+    lookup(key, None)
+}
+
+// Function to get a message with its {name} placeholders filled in
+pub fn tr_with(key: &str, args: &[(&str, &dyn Display)]) -> String{
+    fill(lookup(key, None), args)
+}
+
+// Function to get the plural form of a message for `count`, which also fills the {count} placeholder
+pub fn tr_count(key: &str, count: u64, args: &[(&str, &dyn Display)]) -> String{
+    fill(lookup(key, Some(count)), args).replace("{count}", &count.to_string())
+}
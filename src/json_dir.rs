@@ -0,0 +1,26 @@
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/*
+The load_json_dir/1 function reads every *.json file in a folder, in file name order, and returns
+each path with its parsed contents. Files that can't be read or parsed are logged and skipped so one
+bad file doesn't hide the rest. A missing folder gives an empty list.
+*/
+pub fn load_json_dir<T: DeserializeOwned>(dir: impl AsRef<Path>) -> Vec<(PathBuf, T)>{
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut files: Vec<_> = entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files.into_iter().filter_map(|path|{
+        match fs::read_to_string(&path).map_err(|e| e.to_string())
+                .and_then(|data| serde_json::from_str::<T>(&data).map_err(|e| e.to_string())){
+            Ok(value) => Some((path, value)),
+            Err(e) => {
+                log::warn!(target: "files", "skipping json file: path={} error={}", path.display(), e);
+                None},
+        }
+    }).collect()
+}
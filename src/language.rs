@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use unicode_normalization::UnicodeNormalization;
+
+// Languages words can be played in
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Language{
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "fr")]
+    French,
+}
+
+impl Language{
+    pub const ALL: [Language; 4] = [Language::English, Language::Spanish, Language::German, Language::French];
+
+    // Function to get the language code used by the word and dictionary APIs
+    pub fn code(&self) -> &'static str{
+        match self{
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::German => "de",
+            Language::French => "fr",
+        }
+    }
+
+    // Function to get the name shown in the language picker, in the language itself
+    pub fn label(&self) -> &'static str{
+        match self{
+            Language::English => "English",
+            Language::Spanish => "Español",
+            Language::German => "Deutsch",
+            Language::French => "Français",
+        }
+    }
+
+    // Function to find a language from its code, e.g. a word pack's "language" field
+    pub fn from_code(code: &str) -> Option<Language>{
+        Language::ALL.into_iter().find(|language| language.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    // Function to tag a word with the language so caches don't mix up words spelled the same in two languages
+    pub fn qualify(&self, word: &str) -> String{
+        match self{
+            Language::English => word.to_string(), // Untagged, so caches from before languages were added still work
+            _ => format!("{}:{}", self.code(), word),
+        }
+    }
+}
+
+/*
+The normalize/1 function puts a word in the form words are compared in: Unicode NFC (so an accented
+letter is always one character, however it was typed or sent) and lowercase. Lowercasing keeps ß as
+ß rather than turning it into "ss", so the number of tiles never changes.
+*/
+pub fn normalize(word: &str) -> String{
+    word.nfc().collect::<String>().to_lowercase()
+}
+
+// Function to strip accents from a letter ('é' -> 'e'); letters without one are returned unchanged
+pub fn base_letter(letter: char) -> char{
+    letter.to_string().nfd().next().unwrap_or(letter)
+}
+
+// Function to check whether a word begins with a letter, ignoring accents on both
+pub fn starts_with_letter(word: &str, letter: char) -> bool{
+    word.chars().next().is_some_and(|first| base_letter(first) == base_letter(letter))
+}
+
+// Language words are currently served and checked in
+static ACTIVE_LANGUAGE: Mutex<Language> = Mutex::new(Language::English);
+
+// Function to change the language of the word sources and dictionary
+pub fn set_active(language: Language){
+    if let Ok(mut active) = ACTIVE_LANGUAGE.lock(){
+        if *active != language{
+            log::info!(target: "language", "language changed: code={}", language.code());
+            *active = language;
+        }
+    }
+}
+
+// Function to get the language words are currently served and checked in
+pub fn active() -> Language{
+    ACTIVE_LANGUAGE.lock().map_or(Language::English, |active| *active)
+}
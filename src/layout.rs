@@ -0,0 +1,175 @@
+use emath::{Pos2, Rect, Vec2};
+
+// Constants for sizing letter tiles
+static MAX_TILE_SIZE: f32 = 50.0;     // Tiles never grow past this, matching the original board
+static MIN_TILE_SIZE: f32 = 28.0;     // Below this tiles wrap onto another row instead of shrinking, while the rows fit the height
+static SPACING_RATIO: f32 = 0.1;      // Gap between tiles as a fraction of the tile size
+static SIDE_MARGIN: f32 = 20.0;       // Space kept clear at the left and right of the board
+static BOTTOM_MARGIN: f32 = 45.0;     // Space kept clear under the answer tray
+static TRAY_PADDING: f32 = 5.0;       // Space between the answer tiles and the tray outline
+static BLOCK_GAP: f32 = 30.0;         // Minimum space between the rack and the answer tray
+
+// Struct describing how a board of tiles fits into the game area
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardLayout{
+    pub tile_size: f32, // Width/height of a tile in points
+    pub spacing: f32,   // Gap between neighbouring tiles in points
+    pub columns: usize, // Tiles per row
+    pub rows: usize,    // Rows needed for every tile
+    pub tiles: usize,   // Number of tiles laid out
+    rack_top: f32,      // Top edge of the scrambled rack
+    answer_top: f32,    // Top edge of the answer tiles
+    center_x: f32,      // Horizontal center of the board
+    pixels_per_point: f32,
+}
+
+impl Default for BoardLayout{
+    fn default() -> Self{
+        BoardLayout::compute(Rect::from_min_size(Pos2::ZERO, Vec2::splat(600.0)), 1, 1.0)
+    }
+}
+
+impl BoardLayout{
+    /*
+    The compute/3 function fits `tiles` tiles into `area`.
+    Tiles shrink from MAX_TILE_SIZE until one row fits the width; if they would have to go below
+    MIN_TILE_SIZE they stay at that size and wrap onto extra rows instead. When the rows of the rack
+    and the answer tray would not both fit the height, tiles shrink below MIN_TILE_SIZE until they do.
+    Sizes are rounded to whole physical pixels so tile edges stay crisp at any DPI.
+    */
+    pub fn compute(area: Rect, tiles: usize, pixels_per_point: f32) -> Self{
+        let tiles = tiles.max(1);
+        let usable_width = (area.width() - 2.0 * SIDE_MARGIN).max(MIN_TILE_SIZE);
+        let block_room = (area.height() - BOTTOM_MARGIN - 3.0 * TRAY_PADDING - BLOCK_GAP) / 2.0; // Height for each of the rack and the tray
+        let pixel = 1.0 / pixels_per_point;
+        let fit = |tile_size: f32|{
+            let spacing = snap_to_pixels(tile_size * SPACING_RATIO, pixels_per_point);
+            let columns = (((usable_width + spacing) / (tile_size + spacing)).floor() as usize).clamp(1, tiles);
+            let rows = tiles.div_ceil(columns);
+            (spacing, columns, rows, rows as f32 * tile_size + (rows - 1) as f32 * spacing)
+        };
+
+        let fitted = usable_width / (tiles as f32 * (1.0 + SPACING_RATIO) - SPACING_RATIO);
+        let mut tile_size = snap_to_pixels(fitted.clamp(MIN_TILE_SIZE, MAX_TILE_SIZE), pixels_per_point);
+        while fit(tile_size).3 > block_room && tile_size > pixel{
+            tile_size -= pixel;
+        }
+        let (spacing, columns, rows, block_height) = fit(tile_size);
+
+        // Answer tray sits at the bottom; the rack is centered but kept clear of the tray
+        let answer_top = area.bottom() - BOTTOM_MARGIN - TRAY_PADDING - block_height;
+        let rack_top = (area.center().y - block_height / 2.0)
+            .min(answer_top - TRAY_PADDING - BLOCK_GAP - block_height)
+            .max(area.top() + TRAY_PADDING);
+
+        Self{
+            tile_size,
+            spacing,
+            columns,
+            rows,
+            tiles,
+            rack_top: snap_to_pixels(rack_top, pixels_per_point),
+            answer_top: snap_to_pixels(answer_top, pixels_per_point),
+            center_x: area.center().x,
+            pixels_per_point}
+    }
+
+    // Function to get the top left corner of every tile in the scrambled rack
+    pub fn rack_anchors(&self) -> Vec<Pos2>{
+        self.anchors(self.rack_top)
+    }
+
+    // Function to get the top left corner of every tile in the answer tray
+    pub fn answer_anchors(&self) -> Vec<Pos2>{
+        self.anchors(self.answer_top)
+    }
+
+    // Function to get the rectangle of the answer tray around all answer slots
+    pub fn answer_tray(&self) -> Rect{
+        let width = self.columns as f32 * (self.tile_size + self.spacing) - self.spacing;
+        let height = self.rows as f32 * (self.tile_size + self.spacing) - self.spacing;
+        Rect::from_min_size(
+            Pos2::new(self.center_x - width / 2.0, self.answer_top),
+            Vec2::new(width, height))
+            .expand(TRAY_PADDING)
+    }
+
+    // Function to lay tiles out in rows, centering each row (including a shorter last row)
+    fn anchors(&self, top: f32) -> Vec<Pos2>{
+        let step = self.tile_size + self.spacing;
+        (0..self.tiles).map(|i|{
+            let row = i / self.columns;
+            let in_row = self.columns.min(self.tiles - row * self.columns);
+            let row_width = in_row as f32 * step - self.spacing;
+            Pos2::new(
+                snap_to_pixels(self.center_x - row_width / 2.0 + (i % self.columns) as f32 * step, self.pixels_per_point),
+                top + row as f32 * step)
+        }).collect()
+    }
+}
+
+// Struct describing where the tiles of a square letter grid go in the game area
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridLayout{
+    pub tile_size: f32,
+    pub anchors: Vec<Pos2>, // Top left corner of every cell, row by row
+}
+
+impl GridLayout{
+    // Function to fit a `size`×`size` grid into the middle of `area`, leaving room above it for the traced word
+    pub fn compute(area: Rect, size: usize, pixels_per_point: f32) -> Self{
+        let size = size.max(1);
+        let side = (area.width() - 2.0 * SIDE_MARGIN).min(area.height() - 2.0 * BOTTOM_MARGIN).max(MIN_TILE_SIZE);
+        let tile_size = snap_to_pixels((side / (size as f32 * (1.0 + SPACING_RATIO))).clamp(MIN_TILE_SIZE, 1.5 * MAX_TILE_SIZE), pixels_per_point);
+        let step = tile_size * (1.0 + SPACING_RATIO);
+        let width = size as f32 * step - tile_size * SPACING_RATIO;
+        let corner = area.center() - Vec2::splat(width / 2.0) + Vec2::new(0.0, BOTTOM_MARGIN / 2.0);
+        let anchors = (0..size * size)
+            .map(|cell| Pos2::new(
+                snap_to_pixels(corner.x + (cell % size) as f32 * step, pixels_per_point),
+                snap_to_pixels(corner.y + (cell / size) as f32 * step, pixels_per_point)))
+            .collect();
+        Self{ tile_size, anchors }
+    }
+}
+
+// Function to round a length in points to a whole number of physical pixels
+fn snap_to_pixels(points: f32, pixels_per_point: f32) -> f32{
+    (points * pixels_per_point).round() / pixels_per_point
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn area(width: f32, height: f32) -> Rect{
+        Rect::from_min_size(Pos2::ZERO, Vec2::new(width, height))
+    }
+
+    #[test]
+    fn single_tile_is_full_size_and_centered(){
+        let layout = BoardLayout::compute(area(600.0, 600.0), 1, 1.0);
+        assert_eq!((layout.tile_size, layout.columns, layout.rows), (MAX_TILE_SIZE, 1, 1));
+        let anchor = layout.rack_anchors()[0];
+        assert_eq!(anchor.x + layout.tile_size / 2.0, 300.0);
+    }
+
+    #[test]
+    fn tiles_wrap_at_the_minimum_size(){
+        let layout = BoardLayout::compute(area(400.0, 600.0), 20, 1.0);
+        assert_eq!(layout.tile_size, MIN_TILE_SIZE);
+        assert!(layout.rows > 1);
+        assert_eq!(layout.rack_anchors().len(), 20);
+        let right_edge = layout.rack_anchors().iter().map(|anchor| anchor.x + layout.tile_size).fold(0.0, f32::max);
+        assert!(right_edge <= 400.0 - SIDE_MARGIN);
+    }
+
+    #[test]
+    fn rack_stays_clear_of_the_tray_when_the_height_runs_out(){
+        let layout = BoardLayout::compute(area(400.0, 300.0), 60, 1.0);
+        assert!(layout.tile_size < MIN_TILE_SIZE);
+        let rack_bottom = layout.rack_anchors().iter().map(|anchor| anchor.y + layout.tile_size).fold(0.0, f32::max);
+        assert!(rack_bottom < layout.answer_tray().top());
+        assert!(layout.rack_anchors()[0].y >= 0.0);
+    }
+}
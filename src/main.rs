@@ -44,22 +44,68 @@ mod game_state;
 mod api;
 mod shape_builder;
 mod ui_elements;
-
-use api::get_scrambled_word;
-use eframe::egui::{Event, FontFamily, FontId, FontSelection};
+mod answer_editor;
+mod animation;
+mod settings;
+mod feedback;
+mod layout;
+mod theme;
+mod accessibility;
+mod debug_overlay;
+mod dev_console;
+mod http;
+mod dictionary;
+mod difficulty;
+mod dictionary_cache;
+mod prefetch;
+mod word_history;
+mod word_filter;
+mod word_pack;
+mod language;
+mod i18n;
+mod definitions;
+mod game_mode;
+mod phrase;
+mod grid;
+mod json_dir;
+
+use eframe::egui::Event;
 use eframe::{App, Frame};
-use eframe::egui::{self, CentralPanel, Color32, Context, text::Fonts, FontDefinitions, Key, Painter, Pos2, Rect, Rounding, Shape, SidePanel, Stroke, TopBottomPanel, Vec2};
+use eframe::egui::{self, CentralPanel, Context, Rect, Sense, Shape, SidePanel, TopBottomPanel, Vec2, WidgetInfo, WidgetType};
 use emath::Align2;
-use shape_builder::{ShapeAttributes, RoundingType, Dimensions};
-use ui_elements::{guess_boxes, letter_square, GenerateAnchors, GenerateUiShapes, UiElements};
-use std::any::Any;
-use std::borrow::Borrow;
-use std::env;
-use std::sync::mpsc::Receiver;
+use shape_builder::MorphShape;
+use ui_elements::{guess_boxes, GenerateAnchors, GenerateUiShapes, TileSlot, UiElements};
+use answer_editor::{EditAnswer, MoveTiles, TileAction, TileHistory};
+use animation::TileAnimator;
+use settings::Settings;
+use feedback::LetterFeedback;
+use theme::Theme;
+use accessibility::AccessibilitySettings;
+use debug_overlay::{DebugOverlay, OverlayDetails};
+use dev_console::DevConsole;
+use word_pack::WordPack;
+use language::Language;
+use i18n::{tr, tr_count, tr_with};
+use definitions::{Clue, Glossary};
+use game_mode::GameMode;
+use grid::TraceWords;
+use layout::GridLayout;
+use std::sync::mpsc::{Receiver, TryRecvError};
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 use crate::game_state::{ValidateAnswer, UpdateGameVariables};
-use regex::Regex;
+use crate::api::WordCheck;
+
+static SETTINGS_KEY: &str = "settings"; // Key the settings are saved under in eframe's storage
+
+// Struct for a guess being checked in the background, with the board as it was when it was submitted
+struct PendingGuess {
+    input: String,
+    rack: String,
+    tray: String,
+    blank: Option<char>,
+    word: String,
+}
 
 #[derive(Deserialize, Serialize)]
 #[serde(default)]
@@ -68,9 +114,24 @@ pub struct WordUnscramblerApp {
     guess_history: Vec<(String, bool)>,
     input_text: String,
     #[serde(skip)]
+    cursor: usize,
+    blank_letter: Option<char>, // Letter typed onto the blank tile in missing-letter mode
+    #[serde(skip)]
+    grid_path: Vec<usize>,      // Grid cells traced so far in grid mode
+    #[serde(skip)]
+    trace_start: Option<usize>, // Path length when the mouse was pressed, to tell a drag from a click
+    #[serde(skip)]
+    tile_history: TileHistory,
+    #[serde(skip)]
     timer_start: Instant,
     #[serde(skip)]
-    validation_receiver: Option<Receiver<(String, bool)>>,
+    validation_receiver: Option<Receiver<(String, WordCheck)>>, // Guess or traced word being checked
+    #[serde(skip)]
+    pending_guess: Option<PendingGuess>,
+    #[serde(skip)]
+    unchecked_guess: Option<String>, // Guess the dictionary couldn't be asked about, left in the tray to try again
+    #[serde(skip)]
+    solved_tiles: Option<String>, // Solved answer kept in the tray while its pop plays
     #[serde(skip)]
     scrambled_word_receiver: Option<Receiver<(String, String)>>,
     game_over: bool,
@@ -79,6 +140,33 @@ pub struct WordUnscramblerApp {
     ui_elements: UiElements,
     #[serde(skip)]
     game_space: Rect,
+    #[serde(skip)]
+    animator: TileAnimator,
+    settings: Settings,
+    #[serde(skip)]
+    show_settings: bool,
+    feedback_rows: Vec<(String, Vec<LetterFeedback>)>,
+    revealed_word: Option<String>,
+    #[serde(skip)]
+    themes: Vec<Theme>,
+    #[serde(skip)]
+    applied_look: Option<(String, AccessibilitySettings)>, // Theme name and accessibility settings currently applied to egui
+    #[serde(skip)]
+    font_missing: bool,
+    #[serde(skip)]
+    debug_overlay: DebugOverlay,
+    #[serde(skip)]
+    dev_console: DevConsole,
+    #[serde(skip)]
+    show_start_screen: bool,
+    #[serde(skip)]
+    packs: Vec<WordPack>,
+    #[serde(skip)]
+    selected_pack: Option<usize>, // Index into packs picked on the start screen, None for random words
+    #[serde(skip)]
+    glossary: Glossary,           // Words solved or skipped this game, with their definitions
+    #[serde(skip)]
+    clue: Clue,                   // Definition of the current word, in definition-clue mode
 }
 
 impl Default for WordUnscramblerApp {
@@ -88,18 +176,55 @@ impl Default for WordUnscramblerApp {
             game_state: game_state::GameState::new(),
             guess_history: Vec::new(),
             input_text: String::new(),
+            cursor: 0,
+            blank_letter: None,
+            grid_path: Vec::new(),
+            trace_start: None,
+            tile_history: TileHistory::default(),
             timer_start: Instant::now(),
             validation_receiver: None,
+            pending_guess: None,
+            unchecked_guess: None,
+            solved_tiles: None,
             scrambled_word_receiver: None,
             game_over: false,
             correct: String::new(),
             ui_elements: UiElements::default(),
             game_space: Rect::EVERYTHING,
+            animator: TileAnimator::default(),
+            settings: Settings::default(),
+            show_settings: false,
+            feedback_rows: Vec::new(),
+            revealed_word: None,
+            themes: theme::load_themes(),
+            applied_look: None,
+            font_missing: false,
+            debug_overlay: DebugOverlay::default(),
+            dev_console: DevConsole::default(),
+            show_start_screen: true,
+            packs: word_pack::load_packs(),
+            selected_pack: None,
+            glossary: Glossary::default(),
+            clue: Clue::default(),
         }
     }
 }
 
+impl WordUnscramblerApp {
+    // Function to create the app, restoring the settings saved by the last run
+    fn new(cc: &eframe::CreationContext) -> Self {
+        let settings = cc.storage.and_then(|storage| eframe::get_value(storage, SETTINGS_KEY)).unwrap_or_default();
+        Self{ settings, ..Self::default() }
+    }
+}
+
 impl App for WordUnscramblerApp {
+    // Function to save the settings so the next run starts with them, along with any dictionary answers not yet saved
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SETTINGS_KEY, &self.settings);
+        dictionary_cache::flush();
+    }
+
     /*
     The update/3 function updates the state of the WordUnscramblerApp.
     Arguments:
@@ -111,12 +236,64 @@ impl App for WordUnscramblerApp {
     The function returns immediately after displaying the game over message.
      */
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+        self.switch_theme(ctx);
+        word_history::set_window(self.settings.repeat_window);
+        word_filter::configure(&self.settings.content_filter);
+        i18n::set_locale(&self.settings.ui_language);
+        self.glossary.receive();
+        self.receive_validation();
+        if self.game_state.receive_word() {
+            self.unchecked_guess = None;
+            self.input_text.clear();
+            self.reset_editor();
+            self.feedback_rows.clear();
+        }
+        if !self.animator.is_popping() {
+            self.solved_tiles = None;
+        }
+
+        // Start screen: pick a word pack, then start the clock
+        if self.show_start_screen {
+            let mut play = false;
+            CentralPanel::default().show(ctx, |ui| {
+                ui.heading(tr("app.title"));
+                ui.label(tr("start.intro"));
+                ui.separator();
+                egui::ComboBox::from_label(tr("start.language"))
+                    .selected_text(self.game_state.language.label())
+                    .show_ui(ui, |ui| {
+                        for language in Language::ALL {
+                            ui.selectable_value(&mut self.game_state.language, language, language.label());
+                        }
+                    });
+                game_mode::mode_picker(ui, &mut self.game_state.mode);
+                if self.game_state.mode == GameMode::Grid {
+                    ui.add(egui::Slider::new(&mut self.game_state.grid_size, grid::MIN_SIZE..=grid::MAX_SIZE).text(tr("start.grid_size")));
+                }
+                ui.label(tr("start.word_pack"));
+                word_pack::pack_picker(ui, &self.packs, &mut self.selected_pack);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    play = ui.button(tr("start.play")).clicked();
+                    if ui.button(tr("common.settings")).clicked() {
+                        self.show_settings = !self.show_settings;
+                    }
+                });
+            });
+            settings::settings_window(ctx, &mut self.show_settings, &mut self.settings, &self.themes, self.font_missing);
+
+            if play {
+                self.start_game();
+            }
+            ctx.request_repaint_after(Duration::from_millis(100));
+            return;
+        }
+
         if self.game_state.scrambled_word.is_empty() && self.input_text.is_empty() && self.game_state.start{
             self.game_state.get_new_word();
             self.game_state.start = false;
         }
 
-        let scrambled_word = &self.game_state.scrambled_word.clone();
         // Update time left
         let time_remaining = if let Some(res) = self.game_state.time_alotted.checked_sub(self.timer_start.elapsed()) {
             res
@@ -126,19 +303,56 @@ impl App for WordUnscramblerApp {
             Duration::ZERO //Prevent overflow by setting duration to zero if time elapsed is greater than time alotted
         };
 
+        self.debug_overlay.show(ctx, OverlayDetails {
+            game_state: &self.game_state,
+            input_text: &self.input_text,
+            cursor: self.cursor,
+            time_remaining});
+        if self.dev_console.show(ctx, self.debug_overlay.enabled, &mut self.game_state) {
+            self.input_text.clear();
+            self.reset_editor();
+            self.feedback_rows.clear();
+        }
+
         // If game is over, display "Game Over" message
         if self.game_over {
             CentralPanel::default().show(ctx, |ui| {
-                ui.heading("Game Over!");
-                ui.label(format!("Final Score: {}", self.game_state.score));
-                ui.label("Thank you for playing!");
-                let url = format!("https://twitter.com/intent/tweet?text=I+just+got+a+score+of+{}+in+word+unscrambler", self.game_state.score);
+                ui.heading(tr("game_over.title"));
+                ui.label(tr_with("game_over.final_score", &[("score", &self.game_state.score)]));
+                ui.label(tr("game_over.thanks"));
+                let url = format!("https://twitter.com/intent/tweet?text={}", url_encode(&tr_with("game_over.tweet", &[("score", &self.game_state.score)])));
 
                 ui.horizontal(|ui| {
-                    ui.label("Share your score");
+                    ui.label(tr("game_over.share"));
                     ui.add_space(10.0);
                     ui.hyperlink(url);
                 });
+
+                if let Some(grid) = &self.game_state.grid {
+                    ui.separator();
+                    ui.heading(tr_with("game_over.grid_words", &[("found", &grid.found.len()), ("total", &grid.possible.len())]));
+                    ui.horizontal_wrapped(|ui| {
+                        let extra = grid.found.iter().filter(|word| !grid.possible.contains(word)); // Found with the online dictionary
+                        for word in grid.possible.iter().chain(extra) {
+                            if grid.found.contains(word) {
+                                ui.label(egui::RichText::new(word).strong().color(self.ui_elements.theme.palette.correct));
+                            } else {
+                                ui.weak(word);
+                            }
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.heading(tr("game_over.glossary"));
+                if self.glossary.entries.is_empty() {
+                    ui.weak(tr("game_over.no_words"));
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for entry in &self.glossary.entries {
+                        definitions::word_card(ui, entry);
+                    }
+                });
             });
 
             // Request repaint every 100ms to keep the UI responsive
@@ -148,127 +362,481 @@ impl App for WordUnscramblerApp {
 
         // Build the UI
         TopBottomPanel::top("timer_bar").show(ctx, |ui|{ //Timer
-            ui.heading(format!("Time left: {} seconds", time_remaining.as_secs()))
+            ui.horizontal(|ui|{
+                ui.heading(tr_count("top.time_left", time_remaining.as_secs(), &[]))
+                    .widget_info(|| WidgetInfo::labeled(WidgetType::ProgressIndicator, true, tr_count("top.time_remaining", time_remaining.as_secs(), &[])));
+                if ui.button(tr("common.settings")).clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+                if !http::is_online() {
+                    ui.colored_label(self.ui_elements.theme.palette.incorrect, tr("top.offline"));
+                }
+                if let Some(guess) = &self.unchecked_guess {
+                    ui.label(tr_with("top.unchecked", &[("word", guess)]));
+                }
+            });
         });//End Side Panel
+
+        settings::settings_window(ctx, &mut self.show_settings, &mut self.settings, &self.themes, self.font_missing);
         
         SidePanel::right("score_and_history").show(ctx, |ui|{ //Score and Guess History
-            ui.heading(format!("Score: {}", self.game_state.score));
+            ui.heading(tr_with("side.score", &[("score", &self.game_state.score)]));
             ui.separator();
-            ui.label(format!("Guess History:"));
-            let mut i = 3.0;
+            ui.label(tr("side.guess_history"));
             for (guess, valid) in &self.guess_history{
-                let guess_container = guess_boxes(ui.available_size(), Pos2::new(ctx.available_rect().right() - ui.available_size().x, 30.0 * i), valid);
+                let (row, _) = ui.allocate_exact_size(Vec2::new(ui.available_width(), 30.0), Sense::hover());
+                let guess_container = guess_boxes(row.size(), row.left_top(), valid, &self.ui_elements.theme);
                 ui.painter().add(guess_container);
-                i += 1.0;
                 ui.painter().text(
                     guess_container.rect.center(),
                     Align2::CENTER_CENTER,
-                    guess,
-                    FontId::new(
-                        20.0,
-                        FontFamily::Monospace),
-                    Color32::WHITE);
+                    if self.settings.accessibility.history_markers {
+                        format!("{} {}", accessibility::history_marker(*valid), guess)
+                    } else {
+                        guess.clone()
+                    },
+                    self.ui_elements.theme.history_font(),
+                    self.ui_elements.theme.palette.history_text);
+            }
+
+            if !self.feedback_rows.is_empty() {
+                ui.separator();
+                ui.label(tr("side.feedback"));
+                for (guess, feedback) in &self.feedback_rows {
+                    let (row, _) = ui.allocate_exact_size(Vec2::new(ui.available_width(), 26.0), Sense::hover());
+                    for (tile, letter) in ui_elements::feedback_row(row.left_top(), guess, feedback, &self.ui_elements.theme) {
+                        ui.painter().add(tile);
+                        ui.painter().text(tile.rect.center(), Align2::CENTER_CENTER, letter, self.ui_elements.theme.tile_font(tile.rect.height()), self.ui_elements.theme.palette.tile_letter);
+                    }
+                }
+            }
+            if self.settings.limit_guesses {
+                ui.label(tr_count("side.guesses_left", self.settings.max_guesses.saturating_sub(self.game_state.wrong_guesses).into(), &[]));
+            }
+            if let Some(grid) = self.game_state.grid.as_ref().filter(|_| self.game_state.mode == GameMode::Grid) {
+                ui.separator();
+                ui.label(tr_with("side.grid_found", &[("found", &grid.found.len()), ("total", &grid.possible.len())]));
+                for word in &grid.found {
+                    ui.label(format!("{} +{}", word, grid::points(word)));
+                }
+            }
+            if self.game_state.mode == GameMode::Chain {
+                ui.separator();
+                ui.label(tr_with("side.chain", &[("length", &self.game_state.chain_length), ("multiplier", &(self.game_state.chain_length + 1))]));
+                if let Some(letter) = self.game_state.chain_letter {
+                    ui.strong(tr_with("side.chain_letter", &[("letter", &letter.to_uppercase())]));
+                }
+            }
+            if self.game_state.mode == GameMode::Phrase {
+                ui.label(tr_with("side.phrase_words", &[("lengths", &phrase::word_lengths(&self.game_state.original_word))]));
+            }
+            if self.game_state.mode == GameMode::MissingLetter {
+                ui.label(tr("side.blank_tip"));
+            }
+            let decoys = self.game_state.mode.decoys(self.game_state.level);
+            if decoys > 0 {
+                ui.label(tr_count("side.decoys", decoys as u64, &[]));
+            }
+            if let Some(word) = &self.revealed_word {
+                ui.label(tr_with("side.word_was", &[("word", word)]));
+            }
+            if let Some(hint) = word_pack::hint(&self.game_state.original_word) {
+                ui.separator();
+                ui.label(tr_with("side.hint", &[("hint", &hint)]));
+            }
+            if let Some(entry) = self.glossary.latest() {
+                ui.separator();
+                ui.label(tr("card.title"));
+                definitions::word_card(ui, entry);
             }
         });//End Side Panel
 
+        if self.game_state.mode == GameMode::DefinitionClue {
+            self.clue.follow(&self.game_state.original_word);
+            TopBottomPanel::bottom("clue_bar").show(ctx, |ui| {
+                definitions::clue_bar(ui, &self.clue, &mut self.game_state.clue_revealed, game_state::CLUE_BONUS);
+            });
+        }
+
         CentralPanel::default().show(ctx, |ui| { //Game Area
                 self.game_space = ctx.available_rect();               
-                self.scrambled_letter_anchors();
-                self.answer_letter_anchors(); 
-
-            if ui.input(|i| i.key_pressed(Key::Enter)) { // If Enter key is pressed
-                        //println!("first one: {}", self.input_text);
-                        //self.submit_input(); // Submit the input
-                        //self.input_text.clear();
-                    }
+            if self.game_state.mode == GameMode::Grid {
+                self.grid_board(ui, ctx);
+                return;
+            }
+                self.layout_board(ctx.pixels_per_point())
+                    .scrambled_letter_anchors()
+                    .answer_letter_anchors();
 
             //Static UI Elements
-            ui.painter().add(ui_elements::scrambled_tray(self.game_state.word_length, ui.ctx().available_rect().center_bottom() - Vec2::from((0.0, 100.0))));
+            ui.painter().add(ui_elements::scrambled_tray(&self.ui_elements.layout, &self.ui_elements.theme));
+            ui.interact(self.ui_elements.layout.answer_tray(), ui.id().with("rack_summary"), Sense::hover())
+                .widget_info(|| WidgetInfo::labeled(WidgetType::Label, true, tr_with("board.rack_summary",
+                    &[("rack", &spell_out(&self.game_state.scrambled_word)), ("answer", &spell_out(&self.input_text))])));
             
-            self.ui_elements.generate_squares(&self.game_state.scrambled_word, &self.input_text);
-
-            for (container, letter) in &self.ui_elements.letter_squares {
-                match container{
-                    Shape::Rect(container) => {
-                        ui.painter().add(*container);
-                        ui.painter().text(
-                            container.rect.center_bottom(),//Center of container
-                            Align2::CENTER_BOTTOM, 
-                            letter,
-                            FontId::new(
-                                40.,
-                                FontFamily::Monospace),
-                            Color32::WHITE);},
-                    _ => ()}//Return the empty container if wrong shape
+            match &self.solved_tiles { // The next word's rack waits until the solved tiles have popped
+                Some(solved) => self.ui_elements.generate_squares("", solved, None),
+                None => self.ui_elements.generate_squares(&self.game_state.scrambled_word, &self.input_text, self.blank_letter),
+            };
+            if let Some((_, feedback)) = self.feedback_rows.last().filter(|(guess, _)| *guess == self.resolved_answer()) {
+                self.ui_elements.tint_answer(feedback); // Tiles keep their colors until the guess is edited
+            }
+            self.animator.animate(&mut self.ui_elements.letter_squares, self.settings.reduced_motion);
+
+            let mut tile_action = None;  // Mouse action to apply once all tiles are drawn
+            let mut dragged_tile = None; // Answer tile being dragged, drawn last so it sits on top
+            let answer_length = self.input_text.chars().count();
+
+            for (container, letter, slot) in &self.ui_elements.letter_squares {
+                if let Shape::Rect(container) = container {
+                    let response = ui.interact(container.rect, ui.id().with(slot), Sense::click_and_drag());
+                    let spoken = if *letter == ' ' { tr("board.blank_tile") } else { letter.to_string() }; // Empty blank tile
+                    response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, true, match slot {
+                        TileSlot::Rack(i) => tr_with("board.rack_tile", &[("position", &(i + 1)), ("letter", &spoken)]),
+                        TileSlot::Answer(i) => tr_with("board.answer_tile", &[("position", &(i + 1)), ("letter", &spoken)])}));
+
+                    match (slot, response.interact_pointer_pos()) {
+                        (TileSlot::Answer(from), Some(pointer)) if response.drag_stopped() =>
+                            tile_action = Some(TileAction::Reorder(*from, self.ui_elements.nearest_answer_slot(pointer, answer_length))),
+                        (TileSlot::Answer(_), Some(pointer)) if response.dragged() => {
+                            dragged_tile = Some((container.move_to(pointer - container.rect.center()).resize(4.0, 4.0), *letter)); // Lift the tile under the cursor
+                            continue;}
+                        (TileSlot::Rack(index), _) if response.clicked() => tile_action = Some(TileAction::Take(*index)),
+                        (TileSlot::Answer(index), _) if response.clicked() => tile_action = Some(TileAction::Return(*index)),
+                        _ => ()}
+
+                    paint_tile(ui, *container, *letter, &self.ui_elements.theme);
+                }
             }
 
+            if let Some((container, letter)) = dragged_tile {
+                paint_tile(ui, container, letter, &self.ui_elements.theme);
+            }
+
+            for start in phrase::boundaries(&self.game_state.original_word) { // Mark where each word of a phrase begins
+                if let Some(gap) = self.ui_elements.caret_anchor(start) {
+                    ui.painter().add(ui_elements::word_boundary(gap, self.ui_elements.layout.tile_size, &self.ui_elements.theme));
+                }
+            }
+
+            if let Some(caret) = self.ui_elements.caret_anchor(self.cursor).filter(|_| self.solved_tiles.is_none()) {
+                ui.painter().add(ui_elements::answer_caret(caret, self.ui_elements.layout.tile_size, &self.ui_elements.theme));
+            }
+
+            if let Some(action) = tile_action.filter(|_| self.solved_tiles.is_none()) {
+                self.apply_tile_action(action);
+            }
+
+            let keyboard_free = !self.dev_console.wants_keyboard(); // Console typing must not move tiles
             ui.input(|input_state|{
-                for event in &input_state.events{
+                for event in input_state.events.iter().filter(|_| keyboard_free){
                    match event{
                         Event::Text(text) => {
-                            let next_char = text.chars().next().unwrap();
-                            if self.game_state.scrambled_word.contains(&String::from(next_char)) {
-                                self.input_text.push(next_char);
-                            }
-                            //eprint!("{}\n", self.input_text);
-                            let re = Regex::new(&format!(r"{}", regex::escape(&next_char.to_string()))).unwrap();
-                            self.game_state.scrambled_word = re.replace(&self.game_state.scrambled_word, "").to_string();},
-
-                        Event::Key {key: egui::Key::Backspace, pressed: true, .. } => {
-                            if !self.input_text.is_empty(){
-                                let last_char = self.input_text.chars().next_back().unwrap();
-                                self.game_state.scrambled_word.push(last_char);
-                                self.input_text.remove(self.input_text.len()-1);}},
+                            for next_char in text.chars() {
+                                self.type_letter(next_char);
+                            }},
 
                         Event::Key {key: egui::Key::Enter, pressed: true, ..  } => {
-                            self.submit_input();
-                            println!("Scrambled Word: {}", self.game_state.scrambled_word);
-                            self.input_text.clear()},
+                            self.submit_input();},
+
+                        Event::Key {key, pressed: true, modifiers, .. } => {
+                            self.handle_editor_key(*key, *modifiers);},
 
                         _ => ()};
 
                 }});//End Input
         });//End Central Panel
 
-        // Request repaint
-        ctx.request_repaint_after(Duration::from_millis(100));
+        // Request repaint, straight away while tiles are still moving
+        if self.animator.is_animating() {
+            ctx.request_repaint();
+        }
+        else {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
     }
 }
 
 impl WordUnscramblerApp {
+   // Function to apply the theme picked in settings whenever it differs from the one in use
+   // along with the accessibility adjustments and fonts
+   fn switch_theme(&mut self, ctx: &Context) {
+       let wanted = (self.settings.theme.clone(), self.settings.accessibility.clone());
+       if self.applied_look.as_ref() == Some(&wanted) {
+           return;
+       }
+       if self.themes.is_empty() {
+           self.themes = theme::load_themes();
+       }
+       if self.applied_look.as_ref().map(|(_, applied)| applied.dyslexia_font) != Some(wanted.1.dyslexia_font) {
+           self.font_missing = !accessibility::install_fonts(ctx, wanted.1.dyslexia_font);
+       }
+       let selected = self.themes.iter().find(|theme| theme.name == self.settings.theme).cloned().unwrap_or_default();
+       let adapted = accessibility::adapt_theme(&selected, &self.settings.accessibility);
+       adapted.apply(ctx);
+       self.settings.theme = selected.name;
+       self.ui_elements.theme = adapted;
+       self.applied_look = Some((self.settings.theme.clone(), self.settings.accessibility.clone()));
+   }
+
+   // Function to leave the start screen and start the clock with the chosen word pack
+   fn start_game(&mut self) {
+       let pack = self.selected_pack.and_then(|index| self.packs.get(index)).cloned();
+       if let Some(language) = pack.as_ref().and_then(|pack| Language::from_code(&pack.language)) {
+           self.game_state.language = language; // A pack's words are checked in the pack's own language
+       }
+       language::set_active(self.game_state.language);
+       self.game_state.pack = pack.as_ref().map(|pack| pack.name.clone());
+       word_pack::set_active(pack);
+       self.timer_start = Instant::now();
+       self.show_start_screen = false;
+   }
+
+   // Function to draw the letter grid and let the player trace words on it with the mouse or keyboard
+   fn grid_board(&mut self, ui: &mut egui::Ui, ctx: &Context) {
+       let Some(grid) = self.game_state.grid.clone() else { return };
+       let layout = GridLayout::compute(self.game_space, grid.size, ctx.pixels_per_point());
+       let theme = self.ui_elements.theme.clone();
+       let mut submit = false;
+
+       ui.vertical_centered(|ui| {
+           ui.heading(if self.grid_path.is_empty() { tr("grid.prompt") } else { grid.word(&self.grid_path) });
+           ui.horizontal(|ui| {
+               submit = ui.add_enabled(self.grid_path.len() >= grid::MIN_WORD_LENGTH, egui::Button::new(tr("grid.submit"))).clicked();
+               if ui.button(tr("grid.clear")).clicked() {
+                   self.grid_path.clear();
+               }
+           });
+       });
+
+       // Pressing a tile starts or continues a path; dragging over tiles traces it and letting go submits the traced word
+       let (pressed, down, released, pointer) = ctx.input(|input|
+           (input.pointer.primary_pressed(), input.pointer.primary_down(), input.pointer.primary_released(), input.pointer.interact_pos()));
+       for (cell, anchor) in layout.anchors.iter().enumerate() {
+           let rect = Rect::from_min_size(*anchor, Vec2::splat(layout.tile_size));
+           let letter = grid.letters[cell];
+           ui.interact(rect, ui.id().with(("grid_cell", cell)), Sense::click_and_drag())
+               .widget_info(|| WidgetInfo::labeled(WidgetType::Button, true, tr_with("board.grid_tile",
+                   &[("row", &(cell / grid.size + 1)), ("column", &(cell % grid.size + 1)), ("letter", &letter)])));
+           // Only the middle of a tile counts, so a diagonal drag doesn't catch the tiles it passes between
+           if down && pointer.is_some_and(|pos| rect.shrink(layout.tile_size * 0.2).contains(pos)) {
+               grid::extend_path(&grid, &mut self.grid_path, cell, pressed);
+               if pressed {
+                   self.trace_start = Some(self.grid_path.len());
+               }
+           }
+
+           let mut tile = ui_elements::letter_square(*anchor, layout.tile_size, &theme);
+           if self.grid_path.contains(&cell) {
+               tile.fill = theme.feedback_color(LetterFeedback::Correct);
+           }
+           paint_tile(ui, tile, letter, &theme);
+       }
+       if released {
+           if self.trace_start.is_some_and(|start| self.grid_path.len() > start) { // Dragged across tiles rather than clicked one
+               submit = true;
+           }
+           self.trace_start = None;
+       }
+
+       let centers: Vec<emath::Pos2> = self.grid_path.iter().map(|cell| layout.anchors[*cell] + Vec2::splat(layout.tile_size / 2.0)).collect();
+       if centers.len() > 1 {
+           ui.painter().add(ui_elements::trace_line(centers, layout.tile_size, &theme));
+       }
+
+       // Typing a letter extends the path to a neighbouring tile with that letter
+       let keyboard_free = !self.dev_console.wants_keyboard();
+       let events = ui.input(|input_state| input_state.events.clone());
+       for event in events.iter().filter(|_| keyboard_free) {
+           match event {
+               Event::Text(text) => {
+                   for letter in text.chars().flat_map(char::to_lowercase) {
+                       let next = (0..grid.letters.len()).find(|cell| grid.letters[*cell] == letter && !self.grid_path.contains(cell)
+                           && self.grid_path.last().is_none_or(|last| grid.adjacent(*last, *cell)));
+                       if let Some(cell) = next {
+                           self.grid_path.push(cell);
+                       }
+                   }},
+               Event::Key {key: egui::Key::Enter, pressed: true, .. } => submit = true,
+               Event::Key {key: egui::Key::Backspace, pressed: true, .. } => { self.grid_path.pop(); },
+               Event::Key {key: egui::Key::Escape, pressed: true, .. } => self.grid_path.clear(),
+               _ => (),
+           }
+       }
+
+       if submit {
+           self.submit_path();
+       }
+   }
+
+   // Function to start checking the traced word; the path stays on the board until the check comes back
+   fn submit_path(&mut self) {
+       if self.validation_receiver.is_some() { // Still checking the last word
+           return;
+       }
+       self.unchecked_guess = None;
+       self.validation_receiver = self.game_state.check_path(&self.grid_path);
+       if self.validation_receiver.is_none() {
+           self.grid_path.clear();
+       }
+   }
+
+   // Function to pick up the result of a guess or traced word once it has been checked, without holding up the window
+   fn receive_validation(&mut self) {
+       let Some(receiver) = &self.validation_receiver else { return };
+       let received = match receiver.try_recv() {
+           Err(TryRecvError::Empty) => return,
+           result => result,
+       };
+       self.validation_receiver = None;
+       let pending = self.pending_guess.take();
+       match (received, pending) {
+           (Ok((word, check)), _) if self.game_state.mode == GameMode::Grid => self.finish_path(word, check),
+           (Ok((input, check)), Some(pending)) => self.finish_input(pending, input, check),
+           (Ok(_), None) => (),
+           (Err(e), _) => log::error!(target: "game", "error validating guess: {}", e),
+       }
+   }
+
+   // Function to score a checked word traced on the grid; a word that couldn't be checked keeps its path to try again
+   fn finish_path(&mut self, word: String, check: WordCheck) {
+       match self.game_state.score_word(word.clone(), check) {
+           Some(WordCheck::Valid) => {
+               self.guess_history.push((word.clone(), true));
+               self.glossary.add(&word, true);
+           }
+           Some(WordCheck::Invalid) => self.guess_history.push((word, false)),
+           Some(WordCheck::Unverified) => {
+               self.unchecked_guess = Some(word);
+               return;
+           }
+           None => (),
+       }
+       self.grid_path.clear();
+   }
+
    fn submit_input(&mut self) {
         /*  
         The submit_input/1 function processes the user's input in the WordUnscramblerApp.
         It performs the following steps:
         1. Trims and converts the input text to a string.
-        2. If the input is empty, it returns immediately.
-        3. Clears the input text.
-        4. Clones the original word from the game state.
-        5. Creates a new channel for validation.
-        6. Sets the validation receiver to the newly created receiver.
-        7. Spawns a background thread to validate the input:
+        2. If the input is empty, or a guess is still being checked, it returns immediately.
+        3. Remembers the rack, tray and word as they are, so a wrong guess can be put back.
+        4. Sets the validation receiver to the receiver of a background check of the input:
            - Checks if the input is an exact match with the original word.
            - If not an exact match, checks if the input is a valid word and can form an anagram of the original word.
            - Sends the result (input and validation status) back through the channel.
+        The result is picked up by receive_validation on a later frame.
         */
-       let input = self.input_text.trim().to_string();
-       if input.is_empty() {
+       if self.validation_receiver.is_some() || self.game_state.word_receiver.is_some() { // Still checking the last guess or fetching the next word
+           return;
+       }
+       let input = self.resolved_answer().trim().to_string();
+       if input.is_empty() || input.contains(api::BLANK) { // A blank tile in the answer needs a letter first
+           return;
+       }
+       self.unchecked_guess = None;
+       self.pending_guess = Some(PendingGuess {
+           input: input.clone(),
+           rack: self.game_state.scrambled_word.clone(),
+           tray: self.input_text.clone(),
+           blank: self.blank_letter,
+           word: self.game_state.original_word.clone()});
+       self.validation_receiver = Some(self.game_state.check_answer(input));
+   }
+
+   // Function to score a checked guess and update the board; a guess that couldn't be checked stays in the tray to try again
+   fn finish_input(&mut self, pending: PendingGuess, checked: String, check: WordCheck) {
+       let PendingGuess { input, rack: rack_before, tray: tray_before, blank: blank_before, word: word_before } = pending;
+       let check = self.game_state.apply_check(checked, check);
+       if check == WordCheck::Unverified {
+           self.unchecked_guess = Some(input);
            return;
        }
-       self.guess_history.push((self.input_text.clone(), self.game_state.validate_answer(input)));
+       let correct = check == WordCheck::Valid;
+       self.guess_history.push((input.clone(), correct));
+       if correct {
+           self.solved_tiles = Some(self.resolved_answer());
+       }
+       self.input_text.clear();
+       self.reset_editor();
+
+       if correct {
+           self.glossary.add(&word_before, true);
+           self.animator.pop();
+           self.feedback_rows.clear();
+           self.revealed_word = None;
+           return;
+       }
+
+       self.animator.shake();
+       if self.settings.position_feedback {
+           // Leave the guess in the tray so its tiles can be colored
+           self.feedback_rows.push((input.clone(), feedback::position_feedback(&input, &phrase::letters(&self.game_state.original_word))));
+           self.game_state.scrambled_word = rack_before;
+           self.input_text = tray_before;
+           self.blank_letter = blank_before;
+           self.set_cursor(usize::MAX);
+       }
+       if self.settings.limit_guesses && self.game_state.wrong_guesses >= self.settings.max_guesses {
+           self.revealed_word = Some(self.game_state.original_word.clone());
+           self.glossary.add(&word_before, false);
+           self.game_state.skip_word();
+           self.feedback_rows.clear();
+           self.input_text.clear();
+           self.reset_editor();
+       }
     }
 }
 
-// Helper function to check if `input` can be formed from letters in `original`
+// Helper function to spell letters out for screen readers ("a, b, c"), or "none"
+fn spell_out(letters: &str) -> String {
+    if letters.is_empty() {
+        return tr("board.no_letters");
+    }
+    letters.chars().map(String::from).collect::<Vec<_>>().join(", ")
+}
 
+// Helper function to encode text for a URL query ("a b" -> "a+b", accented letters as %XX bytes)
+fn url_encode(text: &str) -> String {
+    text.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+        b' ' => "+".into(),
+        _ => format!("%{:02X}", byte)}).collect()
+}
+
+// Helper function to paint a letter tile and the letter on top of it, with the letter scaled to the tile
+fn paint_tile(ui: &egui::Ui, container: eframe::epaint::RectShape, letter: char, theme: &Theme) {
+    ui.painter().add(container);
+    ui.painter().text(
+        container.rect.center(),//Center of container
+        Align2::CENTER_CENTER, 
+        letter,
+        theme.tile_font(container.rect.height()),
+        theme.palette.tile_letter);
+}
+
+
+// Helper function to fill the dictionary cache from `--prewarm-cache <word list>` before starting
+fn prewarm_cache_from_args() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(path) = args.iter().position(|arg| arg == "--prewarm-cache").and_then(|i| args.get(i + 1)) {
+        match std::fs::read_to_string(path) {
+            Ok(word_list) => log::info!(target: "cache", "prewarmed dictionary cache: path={} words={}", path, dictionary_cache::prewarm(&word_list)),
+            Err(e) => log::error!(target: "cache", "could not read word list: path={} error={}", path, e),
+        }
+    }
+}
 
 fn main() {
     //env::set_var("RUST_BACKTRACE", "1");
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init(); // Logs go to stderr; filter with RUST_LOG
+    prewarm_cache_from_args();
     let native_options = eframe::NativeOptions::default(); // Create default native options
     let _ = eframe::run_native( // Run the native app
         "Word Unscrambler", // Set the app title
         native_options, // Set the native options
-        Box::new(|_cc| Ok(Box::new(WordUnscramblerApp::default()))), // Create a new WordUnscramblerApp instance
+        Box::new(|cc| Ok(Box::new(WordUnscramblerApp::new(cc)))), // Create a new WordUnscramblerApp instance with saved settings
     );
 }
 
@@ -0,0 +1,112 @@
+use crate::difficulty::Tier;
+use crate::language::{self, Language};
+use crate::{word_filter, word_history};
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Phrase lists compiled into the game, one per language; one phrase per line, lines starting with # are comments
+static PHRASE_LIST: &str = include_str!("../assets/phrases.txt");
+static PHRASE_LIST_ES: &str = include_str!("../assets/phrases_es.txt");
+static PHRASE_LIST_DE: &str = include_str!("../assets/phrases_de.txt");
+static PHRASE_LIST_FR: &str = include_str!("../assets/phrases_fr.txt");
+static LETTERS_PER_TIER_LETTER: usize = 2; // A phrase has about twice as many letters as a word of the same tier
+
+// Function to read a phrase list, normalizing each phrase and collapsing runs of spaces
+fn parse_phrase_list(list: &str) -> Vec<String>{
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| language::normalize(&line.split_whitespace().collect::<Vec<_>>().join(" ")))
+        .collect()
+}
+
+// Function to get the phrase list for a language
+fn phrase_list(language: Language) -> &'static [String]{
+    static PHRASES: OnceLock<HashMap<Language, Vec<String>>> = OnceLock::new();
+    let lists = PHRASES.get_or_init(|| Language::ALL.into_iter().map(|language|{
+        let list = match language{
+            Language::English => PHRASE_LIST,
+            Language::Spanish => PHRASE_LIST_ES,
+            Language::German => PHRASE_LIST_DE,
+            Language::French => PHRASE_LIST_FR,
+        };
+        (language, parse_phrase_list(list))}).collect());
+    &lists[&language]
+}
+
+// Function to get the letters of a phrase without its spaces, as they are dealt onto the rack
+pub fn letters(phrase: &str) -> String{
+    phrase.chars().filter(|letter| !letter.is_whitespace()).collect()
+}
+
+// Function to get the letter positions where each word after the first starts, e.g. [5, 8] for "break the ice"
+pub fn boundaries(phrase: &str) -> Vec<usize>{
+    phrase.split_whitespace()
+        .scan(0, |start, word|{ *start += word.chars().count(); Some(*start) })
+        .collect::<Vec<_>>()
+        .split_last()
+        .map_or(Vec::new(), |(_, starts)| starts.to_vec())
+}
+
+// Function to cut an answer into words at the same places the phrase has its spaces
+pub fn split(answer: &str, phrase: &str) -> Vec<String>{
+    let letters: Vec<char> = answer.chars().collect();
+    let mut starts = vec![0];
+    starts.extend(boundaries(phrase).into_iter().filter(|start| *start < letters.len()));
+    starts.push(letters.len());
+    starts.windows(2).map(|range| letters[range[0]..range[1]].iter().collect()).collect()
+}
+
+// Function to get the word lengths of a phrase, e.g. "5 + 3 + 3" for "break the ice"
+pub fn word_lengths(phrase: &str) -> String{
+    phrase.split_whitespace().map(|word| word.chars().count().to_string()).collect::<Vec<_>>().join(" + ")
+}
+
+/*
+The next_phrase/1 function picks a phrase for the given tier. Phrases grow with the tier: the one
+with a letter count closest to twice the tier's word length is chosen, at random among equally
+close ones. Every word must pass the content filter, and recently served phrases are skipped while
+there are others.
+*/
+pub fn next_phrase(tier: Tier) -> Option<String>{
+    let target = tier.length * LETTERS_PER_TIER_LETTER;
+    let allowed = |phrase: &&String| phrase.split_whitespace().all(word_filter::allows);
+    let pick = |candidates: Vec<&String>| -> Option<String>{
+        let closest = candidates.iter().map(|phrase| letters(phrase).chars().count().abs_diff(target)).min()?;
+        let matching: Vec<&&String> = candidates.iter().filter(|phrase| letters(phrase).chars().count().abs_diff(target) == closest).collect();
+        matching.choose(&mut rand::thread_rng()).map(|phrase| phrase.to_string())
+    };
+    let phrases = phrase_list(language::active());
+    pick(phrases.iter().filter(allowed).filter(|phrase| !word_history::is_recent(phrase)).collect())
+        .or_else(|| pick(phrases.iter().filter(allowed).collect())) // Every phrase seen recently; allow a repeat
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn boundaries_mark_where_each_later_word_starts(){
+        assert_eq!(boundaries("break the ice"), vec![5, 8]);
+        assert_eq!(boundaries("word"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn split_cuts_an_answer_at_the_phrase_spaces(){
+        assert_eq!(split("breaktheice", "break the ice"), vec!["break", "the", "ice"]);
+        assert_eq!(split("icethebreak", "break the ice"), vec!["iceth", "ebr", "eak"]);
+    }
+
+    #[test]
+    fn split_keeps_a_short_answer_whole_past_its_end(){
+        assert_eq!(split("brea", "break the ice"), vec!["brea"]);
+        assert_eq!(split("breakth", "break the ice"), vec!["break", "th"]);
+    }
+
+    #[test]
+    fn letters_and_word_lengths_skip_the_spaces(){
+        assert_eq!(letters("break the ice"), "breaktheice");
+        assert_eq!(word_lengths("break the ice"), "5 + 3 + 3");
+    }
+}
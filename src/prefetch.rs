@@ -0,0 +1,104 @@
+use crate::api;
+use crate::difficulty::Tier;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// Constants for how far ahead words are fetched
+static QUEUE_DEPTH: usize = 3;                           // Words kept ready for each tier
+static IDLE_WAIT: Duration = Duration::from_secs(1);     // How long the worker sleeps when every queue is full
+static RETRY_WAIT: Duration = Duration::from_secs(2);    // Pause after a fetch that found no word at all
+
+// Words waiting to be served, keyed by tier (word length and difficulty band)
+struct PrefetchState{
+    tiers: [Tier; 2],                                    // Current tier and the one after it; both are kept full
+    queues: HashMap<Tier, VecDeque<(String, String)>>,   // (scrambled, original) pairs
+    stopped: bool,
+}
+
+struct Shared{
+    state: Mutex<PrefetchState>,
+    wake: Condvar, // Signalled when a word is taken, the tier changes, or the prefetcher is dropped
+}
+
+// Struct for a background worker keeping upcoming words ready so transitions don't wait on the network
+pub struct WordPrefetcher{
+    shared: Arc<Shared>,
+}
+
+impl WordPrefetcher{
+    // Function to start the background worker, filling queues for `tier` and the `upcoming` tier after it
+    pub fn start(tier: Tier, upcoming: Tier) -> Self{
+        let shared = Arc::new(Shared{
+            state: Mutex::new(PrefetchState{ tiers: [tier, upcoming], queues: HashMap::new(), stopped: false }),
+            wake: Condvar::new()});
+        let worker = Arc::clone(&shared);
+        thread::spawn(move || run_worker(worker));
+        Self{ shared }
+    }
+
+    /*
+    The keep_ready/2 function points the worker at a new tier and the `upcoming` tier after it.
+    Queues for any other tier are thrown away, since the game won't ask for them any more.
+    */
+    pub fn keep_ready(&self, tier: Tier, upcoming: Tier){
+        if let Ok(mut state) = self.shared.state.lock(){
+            if state.tiers != [tier, upcoming]{
+                state.tiers = [tier, upcoming];
+                state.queues.retain(|queued_tier, _| *queued_tier == tier || *queued_tier == upcoming);
+                self.shared.wake.notify_all();
+            }
+        }
+    }
+
+    // Function to take a ready word from `tier`, if one has been fetched
+    pub fn take(&self, tier: Tier) -> Option<(String, String)>{
+        let word = self.shared.state.lock().ok()?.queues.get_mut(&tier)?.pop_front();
+        self.shared.wake.notify_all(); // Let the worker top the queue back up
+        word
+    }
+
+    // Function to count the ready words from `tier`, for the debug overlay
+    pub fn ready(&self, tier: Tier) -> usize{
+        self.shared.state.lock().map_or(0, |state| state.queues.get(&tier).map_or(0, VecDeque::len))
+    }
+}
+
+impl Drop for WordPrefetcher{
+    fn drop(&mut self){
+        if let Ok(mut state) = self.shared.state.lock(){
+            state.stopped = true;
+        }
+        self.shared.wake.notify_all();
+    }
+}
+
+// Function run on the worker thread: fetch for whichever wanted tier is short, otherwise wait
+fn run_worker(shared: Arc<Shared>){
+    loop{
+        let needed = {
+            let Ok(state) = shared.state.lock() else { return };
+            if state.stopped{
+                return;
+            }
+            state.tiers.into_iter()
+                .find(|tier| state.queues.get(tier).map_or(0, VecDeque::len) < QUEUE_DEPTH)
+        };
+
+        let Some(tier) = needed else {
+            let Ok(state) = shared.state.lock() else { return };
+            let _ = shared.wake.wait_timeout(state, IDLE_WAIT);
+            continue;
+        };
+
+        match api::next_word(tier, |_| true){ // Blocking fetch, with the network retries and local fallback
+            Some(word) => {
+                let Ok(mut state) = shared.state.lock() else { return };
+                if state.tiers.contains(&tier){ // Tier may have changed while fetching
+                    state.queues.entry(tier).or_default().push_back(word);
+                }},
+            None => thread::sleep(RETRY_WAIT),
+        }
+    }
+}
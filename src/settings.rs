@@ -0,0 +1,78 @@
+use crate::accessibility::{self, AccessibilitySettings};
+use crate::i18n::{self, tr};
+use crate::theme::Theme;
+use crate::word_filter::{self, ContentFilter};
+use crate::word_history;
+use eframe::egui::{self, Context};
+use serde::{Deserialize, Serialize};
+
+// Player preferences, kept in eframe's storage and restored on the next run
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings{
+    pub reduced_motion: bool,    // Draw tiles straight into their slots without gliding, shaking or popping
+    pub position_feedback: bool, // Color answer tiles by position after a wrong guess
+    pub limit_guesses: bool,     // Skip to a new word after too many wrong guesses
+    pub max_guesses: u8,         // Wrong guesses allowed per word when limited
+    pub theme: String,           // Name of the selected theme
+    pub accessibility: AccessibilitySettings,
+    pub repeat_window: usize,    // Recently served words that won't be served again
+    pub content_filter: ContentFilter,
+    pub ui_language: String,     // Locale code of the interface text, e.g. "fr"
+}
+
+impl Default for Settings{
+    fn default() -> Self{
+        Self{
+            reduced_motion: false,
+            position_feedback: false,
+            limit_guesses: false,
+            max_guesses: 6,
+            theme: Theme::default().name,
+            accessibility: AccessibilitySettings::default(),
+            repeat_window: word_history::DEFAULT_WINDOW,
+            content_filter: ContentFilter::default(),
+            ui_language: "en".into()}
+    }
+}
+
+// Function to show the settings window while `open` is true
+pub fn settings_window(ctx: &Context, open: &mut bool, settings: &mut Settings, themes: &[Theme], font_missing: bool){
+    egui::Window::new(tr("common.settings"))
+        .id(egui::Id::new("settings_window")) // Keep the window in place when the title is translated
+        .open(open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui|{
+            egui::ComboBox::from_label(tr("settings.interface_language"))
+                .selected_text(i18n::language_name(&settings.ui_language))
+                .show_ui(ui, |ui|{
+                    for (code, name) in i18n::locales(){
+                        ui.selectable_value(&mut settings.ui_language, code, name);
+                    }
+                });
+            egui::ComboBox::from_label(tr("settings.theme"))
+                .selected_text(settings.theme.as_str())
+                .show_ui(ui, |ui|{
+                    for theme in themes{
+                        ui.selectable_value(&mut settings.theme, theme.name.clone(), theme.name.as_str());
+                    }
+                });
+            ui.checkbox(&mut settings.reduced_motion, tr("settings.reduced_motion"));
+            ui.separator();
+            ui.checkbox(&mut settings.position_feedback, tr("settings.position_feedback"));
+            ui.checkbox(&mut settings.limit_guesses, tr("settings.limit_guesses"));
+            ui.add_enabled(settings.limit_guesses, egui::Slider::new(&mut settings.max_guesses, 1..=10).text(tr("settings.guesses")));
+            ui.separator();
+            ui.add(egui::Slider::new(&mut settings.repeat_window, 0..=word_history::max_window()).text(tr("settings.repeat_window")));
+            if ui.button(tr("settings.reset_history")).clicked(){
+                word_history::reset();
+            }
+            ui.separator();
+            ui.label(tr("settings.word_filter"));
+            word_filter::content_filter_settings(ui, &mut settings.content_filter);
+            ui.separator();
+            ui.label(tr("settings.accessibility"));
+            accessibility::accessibility_settings(ui, &mut settings.accessibility, font_missing);
+        });
+}
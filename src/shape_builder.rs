@@ -1,7 +1,5 @@
-use std::{default, panic::Location};
-
-use eframe::{egui::{ text::LayoutJob, Color32, FontDefinitions, FontId, Galley, Rect, Rounding, Shape, Stroke}, 
-             epaint::{text::layout, FontFamily, Fonts, RectShape, TextShape}};
+use eframe::{egui::{Color32, FontDefinitions, FontId, Rect, Rounding, Stroke}, 
+             epaint::{FontFamily, Fonts, RectShape, TextShape}};
 use emath::{Pos2, Vec2};
 
 #[derive(Default)]
@@ -49,12 +47,12 @@ impl From<Dimensions> for Rect{ //Converts Dimensions to Rect
             Dimensions::HeightWidth(height, width, coords) => //For shapes that have different height/width (i.e., Rectangles, Ovals)
                 Self { 
                     min: coords, // Top left corner
-                    max: coords.max(Pos2::from(coords + Vec2::from((width, height))))}, // Bottom right corner
+                    max: coords.max(coords + Vec2::from((width, height)))}, // Bottom right corner
 
             Dimensions::Uniform(size, coords) =>  // For Circles, Squares, etc...
                 Self { 
                     min: coords,
-                    max: coords.max(Pos2::from(coords + Vec2::from((size, size))))},
+                    max: coords.max(coords + Vec2::from((size, size)))},
 
             _ => 
                 Self { 
@@ -95,7 +93,7 @@ impl From<RoundingType> for Rounding{ // Converts RoundingType to Rounding
 
 
 
-trait MorphShape{ //For modifying shapes after creation
+pub trait MorphShape{ //For modifying shapes after creation
     fn resize(self, height: f32, width: f32) -> Self;
     fn move_to(self, vec_xy: Vec2) -> Self;
 }
@@ -0,0 +1,208 @@
+use crate::feedback::LetterFeedback;
+use crate::json_dir;
+use eframe::egui::{Color32, Context, FontFamily, FontId, TextStyle, Visuals};
+use serde::{Deserialize, Serialize};
+
+// Directory searched for extra theme files (*.json) at startup
+static THEME_DIRECTORY: &str = "themes";
+
+// Colors used for every part of the board
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Palette{
+    #[serde(with = "hex_color")] pub tile_fill: Color32,
+    #[serde(with = "hex_color")] pub tile_outline: Color32,
+    #[serde(with = "hex_color")] pub tile_letter: Color32,
+    #[serde(with = "hex_color")] pub tray_fill: Color32,
+    #[serde(with = "hex_color")] pub tray_outline: Color32,
+    #[serde(with = "hex_color")] pub caret: Color32,
+    #[serde(with = "hex_color")] pub history_fill: Color32,
+    #[serde(with = "hex_color")] pub history_text: Color32,
+    #[serde(with = "hex_color")] pub correct: Color32,            // Outline of correct guesses in the history
+    #[serde(with = "hex_color")] pub incorrect: Color32,          // Outline of wrong guesses in the history
+    #[serde(with = "hex_color")] pub feedback_correct: Color32,   // Letter in the right position
+    #[serde(with = "hex_color")] pub feedback_misplaced: Color32, // Letter in the wrong position
+    #[serde(with = "hex_color")] pub feedback_absent: Color32,    // Letter not in the word
+}
+
+// Shape of tiles and trays
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TileStyle{
+    pub rounding: f32,      // Corner radius of letter tiles
+    pub stroke_width: f32,  // Outline width of letter tiles and the tray
+    pub tray_rounding: f32, // Corner radius of the answer tray
+}
+
+// Font families and sizes for text on the board and in the panels
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Fonts{
+    pub tile_monospace: bool, // Letters on tiles use the monospace font instead of the proportional one
+    pub tile_scale: f32,      // Letter height as a fraction of the tile size
+    pub heading_size: f32,
+    pub body_size: f32,
+    pub history_size: f32,    // Guesses in the history sidebar
+}
+
+// A complete look for the game; built in or loaded from a theme file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Theme{
+    pub name: String,
+    pub dark: bool, // Base egui widgets on the dark visuals instead of the light ones
+    pub palette: Palette,
+    pub tiles: TileStyle,
+    pub fonts: Fonts,
+}
+
+impl Default for Theme{
+    fn default() -> Self{
+        Theme::dark()
+    }
+}
+
+impl Theme{
+    // Original look of the game: black tiles with white letters on a dark gray tray
+    pub fn dark() -> Self{
+        Self{
+            name: "Dark".into(),
+            dark: true,
+            palette: Palette{
+                tile_fill: Color32::BLACK,
+                tile_outline: Color32::WHITE,
+                tile_letter: Color32::WHITE,
+                tray_fill: Color32::DARK_GRAY,
+                tray_outline: Color32::LIGHT_GRAY,
+                caret: Color32::YELLOW,
+                history_fill: Color32::BLACK,
+                history_text: Color32::WHITE,
+                correct: Color32::GREEN,
+                incorrect: Color32::RED,
+                feedback_correct: Color32::from_rgb(83, 141, 78),
+                feedback_misplaced: Color32::from_rgb(181, 159, 59),
+                feedback_absent: Color32::from_rgb(58, 58, 60)},
+            tiles: TileStyle{
+                rounding: 5.0,
+                stroke_width: 2.0,
+                tray_rounding: 3.0},
+            fonts: Fonts{
+                tile_monospace: true,
+                tile_scale: 0.7,
+                heading_size: 18.0,
+                body_size: 12.5,
+                history_size: 20.0}}
+    }
+
+    pub fn light() -> Self{
+        let dark = Theme::dark();
+        Self{
+            name: "Light".into(),
+            dark: false,
+            palette: Palette{
+                tile_fill: Color32::from_rgb(250, 245, 230),
+                tile_outline: Color32::from_rgb(120, 100, 70),
+                tile_letter: Color32::from_rgb(40, 30, 20),
+                tray_fill: Color32::from_rgb(215, 205, 185),
+                tray_outline: Color32::from_rgb(150, 135, 110),
+                caret: Color32::from_rgb(30, 90, 200),
+                history_fill: Color32::WHITE,
+                history_text: Color32::BLACK,
+                correct: Color32::from_rgb(30, 140, 60),
+                incorrect: Color32::from_rgb(200, 40, 40),
+                feedback_correct: Color32::from_rgb(106, 170, 100),
+                feedback_misplaced: Color32::from_rgb(201, 180, 88),
+                feedback_absent: Color32::from_rgb(160, 160, 160)},
+            tiles: TileStyle{
+                rounding: 8.0,
+                ..dark.tiles},
+            fonts: Fonts{
+                tile_monospace: false,
+                ..dark.fonts}}
+    }
+
+    pub fn high_contrast() -> Self{
+        let dark = Theme::dark();
+        Self{
+            name: "High contrast".into(),
+            dark: true,
+            palette: Palette{
+                tile_fill: Color32::BLACK,
+                tile_outline: Color32::YELLOW,
+                tile_letter: Color32::YELLOW,
+                tray_fill: Color32::BLACK,
+                tray_outline: Color32::WHITE,
+                caret: Color32::from_rgb(0, 255, 255),
+                history_fill: Color32::BLACK,
+                history_text: Color32::WHITE,
+                correct: Color32::from_rgb(0, 255, 0),
+                incorrect: Color32::from_rgb(255, 0, 255),
+                feedback_correct: Color32::from_rgb(0, 160, 0),
+                feedback_misplaced: Color32::from_rgb(0, 90, 255),
+                feedback_absent: Color32::from_rgb(90, 90, 90)},
+            tiles: TileStyle{
+                rounding: 2.0,
+                stroke_width: 3.0,
+                tray_rounding: 2.0},
+            fonts: Fonts{
+                heading_size: 22.0,
+                body_size: 15.0,
+                ..dark.fonts}}
+    }
+
+    // Function to pick the tile color for a piece of position feedback
+    pub fn feedback_color(&self, feedback: LetterFeedback) -> Color32{
+        match feedback{
+            LetterFeedback::Correct => self.palette.feedback_correct,
+            LetterFeedback::Misplaced => self.palette.feedback_misplaced,
+            LetterFeedback::Absent => self.palette.feedback_absent,
+        }
+    }
+
+    // Function to get the font for a letter on a tile of the given size
+    pub fn tile_font(&self, tile_size: f32) -> FontId{
+        let family = if self.fonts.tile_monospace { FontFamily::Monospace } else { FontFamily::Proportional };
+        FontId::new(tile_size * self.fonts.tile_scale, family)
+    }
+
+    // Function to get the font for guesses in the history sidebar
+    pub fn history_font(&self) -> FontId{
+        FontId::new(self.fonts.history_size, FontFamily::Monospace)
+    }
+
+    // Function to apply the theme's widget visuals and text sizes to egui
+    pub fn apply(&self, ctx: &Context){
+        ctx.set_visuals(if self.dark { Visuals::dark() } else { Visuals::light() });
+        ctx.style_mut(|style|{
+            for (text_style, font) in style.text_styles.iter_mut(){
+                match text_style{
+                    TextStyle::Heading => font.size = self.fonts.heading_size,
+                    TextStyle::Body | TextStyle::Button | TextStyle::Monospace => font.size = self.fonts.body_size,
+                    _ => ()}
+            }
+        });
+    }
+}
+
+/*
+The load_themes/0 function returns the built in themes followed by any theme files found in the
+themes directory. Files that can't be read or parsed are skipped so one bad file doesn't hide the rest.
+*/
+pub fn load_themes() -> Vec<Theme>{
+    let mut themes = vec![Theme::dark(), Theme::light(), Theme::high_contrast()];
+    themes.extend(json_dir::load_json_dir::<Theme>(THEME_DIRECTORY).into_iter().map(|(_, theme)| theme));
+    themes
+}
+
+// Colors are written as "#RRGGBB" or "#RRGGBBAA" in theme files
+mod hex_color{
+    use eframe::egui::Color32;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color32, serializer: S) -> Result<S::Ok, S::Error>{
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let hex = if a == 255 { format!("#{:02x}{:02x}{:02x}", r, g, b) } else { format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a) };
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Error>{
+        let hex = String::deserialize(deserializer)?;
+        Color32::from_hex(&hex).map_err(|_| D::Error::custom(format!("invalid color {:?}", hex)))
+    }
+}
@@ -1,26 +1,33 @@
-use crate::{shape_builder::{self, TextAttributes}, WordUnscramblerApp};
+use crate::{api, feedback::LetterFeedback, layout::BoardLayout, shape_builder, theme::Theme, WordUnscramblerApp};
 use shape_builder::{ShapeAttributes, Dimensions, RoundingType};
-use eframe::{egui::{Color32, Shape, Stroke}, epaint::{Fonts, RectShape, TextShape}};
-use emath::{Pos2, Rect, Vec2};
-use std::{default::Default, ops::Range};
-use std::ops::Mul;
-
-// Constants for width and spcaing of letter tiles
-static CONTAINER_WIDTH: f32 = 50.0;
-static CONTAINER_BUFFER: f32 = CONTAINER_WIDTH + 5.0;
+use eframe::{egui::{Shape, Stroke}, epaint::RectShape};
+use emath::{Pos2, Vec2};
+use std::default::Default;
+
+// Constants for sizing UI shapes; letter tile sizes come from the board layout
+static FEEDBACK_WIDTH: f32 = 22.0; // Sidebar feedback tiles
+
+// Where a letter tile currently sits; also identifies the tile for mouse interaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TileSlot{
+    Rack(usize),   // Position in the scrambled rack
+    Answer(usize), // Position in the answer tray
+}
 
 #[derive(Default)]
 
 // Struct for keeping elements of UI regarding letter tiles and answer tray
 pub struct UiElements{
-    pub letter_squares: Vec<(Shape, char)>,
+    pub letter_squares: Vec<(Shape, char, TileSlot)>,
     pub answer_anchors: Vec<Pos2>,
     pub scrambled_anchors: Vec<Pos2>,
-    pub trays: Vec<Shape>,
+    pub layout: BoardLayout,
+    pub theme: Theme,
 }
 
 // Anchors established to assign letters to tiles
 pub trait GenerateAnchors{
+    fn layout_board(&mut self, pixels_per_point: f32) -> &mut Self; // Tile size, spacing and rows for the current window
     fn scrambled_letter_anchors(&mut self) -> &mut Self;  // Scrambled letter tiles
     fn answer_letter_anchors(&mut self) -> &mut Self;     // Answer letter tiles
 }
@@ -29,54 +36,35 @@ pub trait GenerateAnchors{
 pub trait GenerateUiShapes{
     fn place_in_scrambled(&self, position: usize) -> Shape;                           // Position for scrambled letter tile
     fn place_in_answer(&self, position: usize) -> Shape;                              // Position for answer letter tile
-    fn generate_squares(&mut self, scrambled: &String, input: &String) -> &mut Self;  // Generate shapes for letter tiles
+    fn blank_square_at(&self, anchor: Pos2) -> Shape;                                 // Blank tile at an anchor position
+    fn generate_squares(&mut self, scrambled: &str, input: &str, blank_letter: Option<char>) -> &mut Self; // Generate shapes for letter tiles
+    fn nearest_answer_slot(&self, pos: Pos2, filled: usize) -> usize;                 // Closest filled answer slot to a point
+    fn caret_anchor(&self, cursor: usize) -> Option<Pos2>;                            // Top of the caret before answer slot `cursor`
+    fn tint_answer(&mut self, feedback: &[LetterFeedback]) -> &mut Self;              // Color answer tiles by position feedback
 }
 
 // Implementation for generating anchors within Word scrambler app
 impl GenerateAnchors for WordUnscramblerApp {
 
+    // Function to fit the board to the game area; anchors below are read from this layout
+    fn layout_board(&mut self, pixels_per_point: f32) -> &mut Self {
+        let tiles = match &self.solved_tiles{
+            Some(solved) => solved.chars().count(),
+            None => self.game_state.scrambled_word.chars().count() + self.input_text.chars().count(),
+        };
+        self.ui_elements.layout = BoardLayout::compute(self.game_space, tiles, pixels_per_point);
+        self
+    }
+
     // Function to calculate anchors for scrambled letter tiles
     fn scrambled_letter_anchors(&mut self) -> &mut Self {
-        // Clear existing anchors and recalculate based on word length
-        self.ui_elements.scrambled_anchors.clear();
-        let mut i: f32 = 1.0;
-
-        // Calculate centering for scrambled letters on the screen
-        for _ in 0..self.game_state.word_length {
-            let offset = (self.game_state.word_length / 2) as f32 * CONTAINER_BUFFER 
-                         + (self.game_state.word_length / 2 - 1) as f32 * 5.0 + 2.5;
-
-            // Calculate centering for letter position within tile
-            self.ui_elements.scrambled_anchors.push(
-                self.game_space.center() 
-                - Vec2::from((offset, 0.0)) 
-                - Vec2::from((CONTAINER_BUFFER - (i * CONTAINER_BUFFER), 0.0))
-            );
-            i += 1.0;
-        }
+        self.ui_elements.scrambled_anchors = self.ui_elements.layout.rack_anchors();
         self
     }
 
     // Function to calculate anchors for answer letter tiles
     fn answer_letter_anchors(&mut self) -> &mut Self {
-
-        // Clear existing anchors and recalculate based on word length
-        self.ui_elements.answer_anchors.clear();
-        let mut i: f32 = 1.0;
-
-        // Calculate centering for answer letters on the screen
-        for _ in 0..self.game_state.word_length {
-            let offset = (self.game_state.word_length / 2) as f32 * CONTAINER_BUFFER 
-                         + (self.game_state.word_length / 2 - 1) as f32 * 5.0;
-
-            // Calculate centering for letter position within tile
-            self.ui_elements.answer_anchors.push(
-                self.game_space.center_bottom() 
-                - Vec2::from((offset, 0.0)) 
-                - Vec2::from((CONTAINER_BUFFER - (i * CONTAINER_BUFFER), 95.0))
-            );
-            i += 1.0;
-        }
+        self.ui_elements.answer_anchors = self.ui_elements.layout.answer_anchors();
         self
     }
 }
@@ -86,65 +74,142 @@ impl GenerateUiShapes for UiElements {
 
     // Function to create tile shape for scrambled letters at given anchor position
     fn place_in_scrambled(&self, position: usize) -> Shape {
-        Shape::Rect(letter_square(self.scrambled_anchors[position]))
+        Shape::Rect(letter_square(self.scrambled_anchors[position], self.layout.tile_size, &self.theme))
     }
     
     // FUnction to create tile shape for answer letters at given anchor position
     fn place_in_answer(&self, position: usize) -> Shape {
-        Shape::Rect(letter_square(self.answer_anchors[position]))
+        Shape::Rect(letter_square(self.answer_anchors[position], self.layout.tile_size, &self.theme))
     }
 
-    // Function to generate the scrambled and answer letter tiles
-    fn generate_squares(&mut self, scrambled: &String, input: &String) -> &mut Self {
+    // Function to create the blank tile shape at an anchor position
+    fn blank_square_at(&self, anchor: Pos2) -> Shape {
+        Shape::Rect(blank_square(anchor, self.layout.tile_size, &self.theme))
+    }
+
+    // Function to generate the scrambled and answer letter tiles; the blank tile shows the letter given to it, if any
+    fn generate_squares(&mut self, scrambled: &str, input: &str, blank_letter: Option<char>) -> &mut Self {
         self.letter_squares.clear();                                  // Clears existing letter tiles
         let scrambled_chars = scrambled.chars().collect::<Vec<_>>();  // Collect scrambled letters
         let input_chars = input.chars().collect::<Vec<_>>();          // Collect user input letters
 
         // Generate and store tiles for each scrambled letter
         for (i, letter) in scrambled_chars.iter().enumerate() {
-            self.letter_squares.push((self.place_in_scrambled(i), *letter));
+            let tile = if *letter == api::BLANK { (self.blank_square_at(self.scrambled_anchors[i]), ' ') } else { (self.place_in_scrambled(i), *letter) };
+            self.letter_squares.push((tile.0, tile.1, TileSlot::Rack(i)));
         }
 
         // Generate and store tiles for each answer letter
         for (i, letter) in input_chars.iter().enumerate() {
-            self.letter_squares.push((self.place_in_answer(i), *letter));
+            let tile = if *letter == api::BLANK { (self.blank_square_at(self.answer_anchors[i]), blank_letter.unwrap_or(' ')) } else { (self.place_in_answer(i), *letter) };
+            self.letter_squares.push((tile.0, tile.1, TileSlot::Answer(i)));
+        }
+        self
+    }
+
+    // Function to find which filled answer slot a dropped tile is closest to
+    fn nearest_answer_slot(&self, pos: Pos2, filled: usize) -> usize {
+        let half_tile = Vec2::splat(self.layout.tile_size / 2.0);
+        self.answer_anchors.iter()
+            .take(filled)
+            .enumerate()
+            .min_by(|(_, a), (_, b)| (**a + half_tile).distance(pos).total_cmp(&(**b + half_tile).distance(pos)))
+            .map_or(0, |(i, _)| i)
+    }
+
+    // Function to find where the caret is drawn: in the gap before answer slot `cursor`
+    fn caret_anchor(&self, cursor: usize) -> Option<Pos2> {
+        let gap = Vec2::from((self.layout.spacing / 2.0, 0.0));
+        let step = Vec2::from((self.layout.tile_size + self.layout.spacing, 0.0));
+        match self.answer_anchors.get(cursor) {
+            Some(anchor) => Some(*anchor - gap),
+            None => self.answer_anchors.last().map(|anchor| *anchor + step - gap), // Caret after the last slot
+        }
+    }
+
+    // Function to fill each answer tile with the color of its feedback
+    fn tint_answer(&mut self, feedback: &[LetterFeedback]) -> &mut Self {
+        for (container, _, slot) in &mut self.letter_squares {
+            if let (Shape::Rect(container), TileSlot::Answer(i)) = (container, slot) {
+                if let Some(letter_feedback) = feedback.get(*i) {
+                    container.fill = self.theme.feedback_color(*letter_feedback);
+                }
+            }
         }
         self
     }
 }
 
 // Function to create tile for letter at given position
-pub fn letter_square(pos: Pos2) ->  RectShape{
+pub fn letter_square(pos: Pos2, size: f32, theme: &Theme) ->  RectShape{
+    let attr = ShapeAttributes{
+        dimensions: Dimensions::Uniform(size, pos),
+        fill_color: theme.palette.tile_fill,
+        rounding: RoundingType::UniformRounding(theme.tiles.rounding),
+        outline: Stroke::from((theme.tiles.stroke_width, theme.palette.tile_outline)),
+    };
+    RectShape::from(attr)
+}
+
+// Function to create the blank tile: hollow, in the tray color with a caret-colored outline, so it stands out from letter tiles
+pub fn blank_square(pos: Pos2, size: f32, theme: &Theme) -> RectShape{
     let attr = ShapeAttributes{
-        dimensions: Dimensions::Uniform(CONTAINER_WIDTH, Pos2::from(pos)),
-        fill_color: Color32::BLACK,
-        rounding: RoundingType::UniformRounding(5.0),
-        outline: Stroke::from((2.0, Color32::WHITE)),
+        dimensions: Dimensions::Uniform(size, pos),
+        fill_color: theme.palette.tray_fill,
+        rounding: RoundingType::UniformRounding(theme.tiles.rounding),
+        outline: Stroke::from((theme.tiles.stroke_width.max(2.0), theme.palette.caret)),
     };
     RectShape::from(attr)
 }
 
-// Function to create tray shape for when answer letters are entered by user at given position
-pub fn scrambled_tray(letters: usize, pos: Pos2) -> RectShape{
-    let tray_width = CONTAINER_BUFFER * letters as f32;
+// Function to create tray shape for when answer letters are entered by user, around the answer slots of the layout
+pub fn scrambled_tray(layout: &BoardLayout, theme: &Theme) -> RectShape{
+    let tray = layout.answer_tray();
     let attr = ShapeAttributes{
-        dimensions: Dimensions::HeightWidth(
-            CONTAINER_WIDTH + 10.0, tray_width + 10.0,
-            pos - Vec2::from((tray_width/2.0, 0.0))),
-        fill_color: Color32::DARK_GRAY,
-        rounding: RoundingType::UniformRounding(3.0),
-        outline: Stroke::from((2.0, Color32::LIGHT_GRAY))
+        dimensions: Dimensions::HeightWidth(tray.height(), tray.width(), tray.min),
+        fill_color: theme.palette.tray_fill,
+        rounding: RoundingType::UniformRounding(theme.tiles.tray_rounding),
+        outline: Stroke::from((theme.tiles.stroke_width, theme.palette.tray_outline))
     };
     RectShape::from(attr)
 }
 
+// Function to create the caret line drawn between answer tiles
+pub fn answer_caret(top: Pos2, height: f32, theme: &Theme) -> Shape{
+    Shape::line_segment([top, top + Vec2::from((0.0, height))], Stroke::from((3.0, theme.palette.caret)))
+}
+
+// Function to create the mark drawn in the answer tray between two words of a phrase, taller than the caret so both show
+pub fn word_boundary(top: Pos2, height: f32, theme: &Theme) -> Shape{
+    let overhang = Vec2::from((0.0, height * 0.2));
+    Shape::line_segment([top - overhang, top + Vec2::from((0.0, height)) + overhang], Stroke::from((2.0, theme.palette.tray_outline)))
+}
+
+// Function to create the line drawn through the centers of the grid tiles being traced
+pub fn trace_line(points: Vec<Pos2>, tile_size: f32, theme: &Theme) -> Shape{
+    Shape::line(points, Stroke::from((tile_size * 0.12, theme.palette.caret.gamma_multiply(0.6))))
+}
+
+// Function to create the small colored tiles for one row of position feedback in the sidebar
+pub fn feedback_row(pos: Pos2, guess: &str, feedback: &[LetterFeedback], theme: &Theme) -> Vec<(RectShape, char)>{
+    guess.chars().zip(feedback).enumerate().map(|(i, (letter, letter_feedback))|{
+        let attr = ShapeAttributes{
+            dimensions: Dimensions::Uniform(FEEDBACK_WIDTH, pos + Vec2::from((i as f32 * (FEEDBACK_WIDTH + 3.0), 0.0))),
+            fill_color: theme.feedback_color(*letter_feedback),
+            rounding: RoundingType::UniformRounding(3.0),
+            outline: Stroke::NONE,
+        };
+        (RectShape::from(attr), letter)
+    }).collect()
+}
+
 // Function to create boxes for guesses in the sidebar
-pub fn guess_boxes(width: Vec2, pos: Pos2, correct: &bool) -> RectShape{
+pub fn guess_boxes(width: Vec2, pos: Pos2, correct: &bool, theme: &Theme) -> RectShape{
     let attr = ShapeAttributes{
         dimensions: Dimensions::HeightWidth(20.0, width.x, pos),
-        fill_color: Color32::BLACK,
+        fill_color: theme.palette.history_fill,
         rounding: RoundingType::UniformRounding(4.0),
-        outline: Stroke::from((2.0, if *correct{Color32::GREEN} else {Color32::RED}))  // green outline for correct and red outline for wrong answers      
+        outline: Stroke::from((2.0, if *correct{theme.palette.correct} else {theme.palette.incorrect}))  // correct and wrong answers get different outlines
     };
     RectShape::from(attr)
 }
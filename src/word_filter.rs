@@ -0,0 +1,115 @@
+use crate::dictionary;
+use crate::i18n::{tr, tr_with};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::sync::{Mutex, OnceLock};
+
+// Constants for where the filter word lists come from
+static BLOCKLIST_FILE: &str = "filters/blocklist.txt"; // Player's own blocked words, optional
+static ALLOWLIST_FILE: &str = "filters/allowlist.txt"; // Player's own allowed words, optional
+static FAMILY_BLOCKLIST: &str = include_str!("../assets/family_blocklist.txt");
+
+// Content filter preferences, saved as part of the settings
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ContentFilter{
+    pub family_friendly: bool, // Block crude and adult words as well as the player's blocklist
+    pub min_frequency: f32,    // Lowest Zipf frequency served; 0 serves words of any frequency
+    pub use_allowlist: bool,   // Only serve words from the allowlist (ignored when it is empty)
+}
+
+impl Default for ContentFilter{
+    fn default() -> Self{
+        Self{
+            family_friendly: true,
+            min_frequency: 0.0,
+            use_allowlist: false}
+    }
+}
+
+// Word lists read once at startup
+struct WordLists{
+    blocked: HashSet<String>,
+    family_blocked: HashSet<String>,
+    allowed: HashSet<String>,
+}
+
+// Function to read a word list, one word per line, skipping blank lines and # comments
+fn parse_list(text: &str) -> HashSet<String>{
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn lists() -> &'static WordLists{
+    static LISTS: OnceLock<WordLists> = OnceLock::new();
+    LISTS.get_or_init(||{
+        let read = |path: &str| fs::read_to_string(path).map(|text| parse_list(&text)).unwrap_or_default();
+        let lists = WordLists{
+            blocked: read(BLOCKLIST_FILE),
+            family_blocked: parse_list(FAMILY_BLOCKLIST),
+            allowed: read(ALLOWLIST_FILE)};
+        log::info!(target: "filter", "word lists loaded: blocked={} allowed={}", lists.blocked.len(), lists.allowed.len());
+        lists
+    })
+}
+
+// Filter currently in effect; set from the settings every frame
+fn active() -> &'static Mutex<ContentFilter>{
+    static ACTIVE: OnceLock<Mutex<ContentFilter>> = OnceLock::new();
+    ACTIVE.get_or_init(|| Mutex::new(ContentFilter::default()))
+}
+
+// Function to change the filter applied to words from every source
+pub fn configure(filter: &ContentFilter){
+    if let Ok(mut active) = active().lock(){
+        if *active != *filter{
+            *active = filter.clone();
+        }
+    }
+}
+
+// Function to check whether only the local word list can satisfy the filter: API words have no frequency
+// and are almost never on the allowlist, so asking the API would only waste requests
+pub fn local_only() -> bool{
+    let filter = active().lock().map(|filter| filter.clone()).unwrap_or_default();
+    filter.min_frequency > 0.0 || (filter.use_allowlist && !lists().allowed.is_empty())
+}
+
+/*
+The allows/1 function decides whether a word may be served as a puzzle. Words must be made only
+of letters and lowercase (the word API marks proper nouns with capitals), and must not be on the
+blocklist, or on the family blocklist in family-friendly mode. The lists block exact words only,
+so inflections are listed separately. With a minimum frequency set, words the local word list has
+no frequency for are rejected, since they are usually the obscure ones.
+*/
+pub fn allows(word: &str) -> bool{
+    let filter = active().lock().map(|filter| filter.clone()).unwrap_or_default();
+    let lists = lists();
+
+    if word.is_empty() || !word.chars().all(|letter| letter.is_alphabetic() && !letter.is_uppercase()){
+        return false;
+    }
+    if lists.blocked.contains(word) || (filter.family_friendly && lists.family_blocked.contains(word)){
+        return false;
+    }
+    if filter.use_allowlist && !lists.allowed.is_empty() && !lists.allowed.contains(word){
+        return false;
+    }
+    filter.min_frequency <= 0.0 || dictionary::frequency(word).is_some_and(|zipf| zipf >= filter.min_frequency)
+}
+
+// Function to draw the content filter part of the settings window
+pub fn content_filter_settings(ui: &mut egui::Ui, filter: &mut ContentFilter){
+    ui.checkbox(&mut filter.family_friendly, tr("filter.family_friendly"));
+    ui.add(egui::Slider::new(&mut filter.min_frequency, 0.0..=6.0).step_by(0.5).text(tr("filter.min_frequency")));
+    let allowlist_size = lists().allowed.len();
+    ui.add_enabled(allowlist_size > 0, egui::Checkbox::new(&mut filter.use_allowlist, tr("filter.use_allowlist")));
+    if allowlist_size == 0{
+        ui.label(tr_with("filter.no_allowlist", &[("path", &ALLOWLIST_FILE)]));
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use crate::language;
+
+// Constants for the served word history
+static HISTORY_FILE: &str = "word_history.json";
+pub static DEFAULT_WINDOW: usize = 500; // Most recent words that won't be served again
+static MAX_KEPT: usize = 5000;          // Largest window offered in settings; older words are forgotten
+
+// How many of the most recently served words are excluded; set from the settings every frame
+static WINDOW: AtomicUsize = AtomicUsize::new(DEFAULT_WINDOW);
+
+// Struct for the words served to the player, newest last, saved between runs
+#[derive(Serialize, Deserialize, Default)]
+struct WordHistory{
+    served: VecDeque<String>,
+}
+
+impl WordHistory{
+    fn load() -> Self{
+        fs::read_to_string(HISTORY_FILE).ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self){
+        match serde_json::to_string(self){
+            Ok(data) => if let Err(e) = fs::write(HISTORY_FILE, data){
+                log::warn!(target: "history", "could not save word history: {}", e);},
+            Err(e) => log::warn!(target: "history", "could not serialize word history: {}", e),
+        }
+    }
+}
+
+fn history() -> &'static Mutex<WordHistory>{
+    static HISTORY: OnceLock<Mutex<WordHistory>> = OnceLock::new();
+    HISTORY.get_or_init(|| Mutex::new(WordHistory::load()))
+}
+
+// Function to change how many recent words are excluded (0 allows repeats)
+pub fn set_window(window: usize){
+    WINDOW.store(window.min(MAX_KEPT), Ordering::Relaxed);
+}
+
+pub fn max_window() -> usize{
+    MAX_KEPT
+}
+
+// Function to check if a word was served within the window
+pub fn is_recent(word: &str) -> bool{
+    let window = WINDOW.load(Ordering::Relaxed);
+    let word = language::normalize(word);
+    history().lock().is_ok_and(|history| history.served.iter().rev().take(window).any(|served| *served == word))
+}
+
+// Function to remember that a word was served and save the history
+pub fn record(word: &str){
+    if let Ok(mut history) = history().lock(){
+        history.served.push_back(language::normalize(word));
+        while history.served.len() > MAX_KEPT{
+            history.served.pop_front();
+        }
+        history.save();
+    }
+}
+
+// Function to forget every served word
+pub fn reset(){
+    if let Ok(mut history) = history().lock(){
+        history.served.clear();
+        history.save();
+    }
+    log::info!(target: "history", "word history reset");
+}
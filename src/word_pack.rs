@@ -0,0 +1,122 @@
+use crate::difficulty::{self, Tier};
+use crate::{dictionary, json_dir, language, word_filter, word_history};
+use crate::i18n::{tr, tr_count, tr_with};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+// Folder word packs are loaded from, one JSON file per pack
+static PACK_DIRECTORY: &str = "packs";
+
+// A word in a pack, written either as a plain string or as an object with a hint and definition
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(from = "PackEntry")]
+pub struct PackWord{
+    pub word: String,
+    pub hint: Option<String>,       // Short clue shown while the word is being played
+    pub definition: Option<String>, // Meaning of the word, for packs of jargon the dictionary won't know
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PackEntry{
+    Plain(String),
+    Detailed{ word: String, hint: Option<String>, definition: Option<String> },
+}
+
+impl From<PackEntry> for PackWord{
+    fn from(entry: PackEntry) -> Self{
+        let (word, hint, definition) = match entry{
+            PackEntry::Plain(word) => (word, None, None),
+            PackEntry::Detailed{ word, hint, definition } => (word, hint, definition),
+        };
+        Self{ word: language::normalize(word.trim()), hint, definition }
+    }
+}
+
+// Struct for a themed set of words played instead of random ones
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WordPack{
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_language")]
+    pub language: String, // Language code of the words, e.g. "en"
+    pub words: Vec<PackWord>,
+}
+
+fn default_language() -> String{
+    "en".into()
+}
+
+impl WordPack{
+    /*
+    The next_word/1 function picks a word from the pack for the given tier, using the same rules as
+    the other word sources: the content filter always applies, recently served words are skipped
+    while there are others, and a word from the tier's band is preferred at the requested length.
+    Only words `wanted` accepts are picked.
+    */
+    pub fn next_word(&self, tier: Tier, wanted: impl Fn(&str) -> bool) -> Option<String>{
+        let words: Vec<&str> = self.words.iter().map(|entry| entry.word.as_str()).collect();
+        let in_tier = |word: &str| word.chars().count() == tier.length && difficulty::band(word) == tier.band;
+        let allowed = |word: &str| wanted(word) && word_filter::allows(word);
+        let fresh = |word: &str| allowed(word) && !word_history::is_recent(word);
+        dictionary::closest_word(&words, tier.length, |word| in_tier(word) && fresh(word))
+            .or_else(|| dictionary::closest_word(&words, tier.length, fresh))
+            .or_else(|| dictionary::closest_word(&words, tier.length, allowed)) // Whole pack seen recently; allow a repeat
+    }
+
+    // Function to find a word's entry in the pack
+    pub fn entry(&self, word: &str) -> Option<&PackWord>{
+        let word = language::normalize(word);
+        self.words.iter().find(|entry| entry.word == word)
+    }
+}
+
+// Pack being played; None means words come from the random word API and local word list
+static ACTIVE_PACK: Mutex<Option<WordPack>> = Mutex::new(None);
+
+// Function to choose the pack words are served from, or None for random words
+pub fn set_active(pack: Option<WordPack>){
+    if let Ok(mut active) = ACTIVE_PACK.lock(){
+        log::info!(target: "pack", "word pack selected: name={}", pack.as_ref().map_or("none", |pack| pack.name.as_str()));
+        *active = pack;
+    }
+}
+
+// Function to get a copy of the pack being played, if any
+pub fn active() -> Option<WordPack>{
+    ACTIVE_PACK.lock().ok()?.clone()
+}
+
+// Function to get the hint for a word in the pack being played
+pub fn hint(word: &str) -> Option<String>{
+    ACTIVE_PACK.lock().ok()?.as_ref()?.entry(word)?.hint.clone()
+}
+
+// Function to read every pack in the packs folder, skipping (and logging) files that don't parse
+pub fn load_packs() -> Vec<WordPack>{
+    json_dir::load_json_dir::<WordPack>(PACK_DIRECTORY).into_iter()
+        .filter_map(|(path, pack)|{
+            if pack.words.is_empty(){
+                log::warn!(target: "pack", "skipping empty word pack: path={}", path.display());
+                return None;
+            }
+            Some(pack)})
+        .collect()
+}
+
+// Function to draw the pack picker on the start screen; `selected` is the index of the chosen pack
+pub fn pack_picker(ui: &mut egui::Ui, packs: &[WordPack], selected: &mut Option<usize>){
+    ui.radio_value(selected, None, tr("pack.random"))
+        .on_hover_text(tr("pack.random_hover"));
+    for (index, pack) in packs.iter().enumerate(){
+        ui.radio_value(selected, Some(index), tr_count("pack.summary", pack.words.len() as u64, &[("name", &pack.name), ("language", &pack.language)]));
+        if !pack.description.is_empty(){
+            ui.indent(("pack_description", index), |ui| ui.weak(&pack.description));
+        }
+    }
+    if packs.is_empty(){
+        ui.weak(tr_with("pack.none_found", &[("folder", &PACK_DIRECTORY)]));
+    }
+}